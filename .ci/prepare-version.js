@@ -22,6 +22,7 @@ const command = cmd.command({
   async handler({ versionType }) {
     exec("git pull --ff-only");
     const nextVersion = updateCargoToml(versionType);
+    exec("./.ci/update-release-keys.sh");
     exec("cargo build --release");
     exec("yarn generate-command-docs --binary-path=./target/release/fnm");
     exec("./docs/record_screen.sh");
@@ -0,0 +1,22 @@
+#!/bin/bash
+
+# Regenerates `keys/release-keys.gpg`, the keyring fnm uses to verify the signatures
+# of Node.js releases, from the keys listed in https://github.com/nodejs/release-keys
+
+set -e
+
+RELEASE_KEYS_URL="https://raw.githubusercontent.com/nodejs/release-keys/main"
+TARGET="$(dirname "$0")/../keys/release-keys.gpg"
+
+GNUPGHOME="$(mktemp -d)"
+export GNUPGHOME
+trap 'rm -rf "$GNUPGHOME"' EXIT
+
+for fingerprint in $(curl -fsSL "$RELEASE_KEYS_URL/keys.list"); do
+  echo "Importing $fingerprint"
+  curl -fsSL "$RELEASE_KEYS_URL/keys/$fingerprint.asc" | gpg --batch --import
+done
+
+mkdir -p "$(dirname "$TARGET")"
+gpg --batch --export >"$TARGET"
+echo "Wrote $TARGET"
@@ -1,3 +1,31 @@
+use std::path::Path;
+
 fn main() {
     embed_resource::compile("fnm-manifest.rc");
+    bundle_release_keys();
+}
+
+/// Copies the Node.js release keyring into `OUT_DIR` so it can be embedded into the binary.
+/// The keyring is generated by `.ci/update-release-keys.sh`, which the release jobs run
+/// before building. Release builds fail without it,
+/// while debug builds embed an empty keyring, so signature verification requires
+/// `--release-keyring` at runtime.
+fn bundle_release_keys() {
+    let source = Path::new("keys/release-keys.gpg");
+    let target = Path::new(&std::env::var("OUT_DIR").unwrap()).join("release-keys.gpg");
+    println!("cargo:rerun-if-changed={}", source.display());
+
+    let keyring = std::fs::read(source).unwrap_or_default();
+    if keyring.is_empty() {
+        assert!(
+            std::env::var("PROFILE").as_deref() != Ok("release"),
+            "{} is missing or empty. Generate it with .ci/update-release-keys.sh",
+            source.display()
+        );
+        println!(
+            "cargo:warning=Can't find {}, signature verification will require `--release-keyring`",
+            source.display()
+        );
+    }
+    std::fs::write(target, keyring).expect("Can't write the release keyring");
 }
@@ -23,7 +23,7 @@ pub fn create_alias(
 }
 
 pub fn list_aliases(config: &FnmConfig) -> std::io::Result<Vec<StoredAlias>> {
-    let vec: Vec<_> = std::fs::read_dir(&config.aliases_dir())?
+    let vec: Vec<_> = std::fs::read_dir(config.aliases_dir())?
         .filter_map(Result::ok)
         .filter_map(|x| TryInto::<StoredAlias>::try_into(x.path().as_path()).ok())
         .collect();
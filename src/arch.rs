@@ -16,10 +16,10 @@ pub enum Arch {
 pub fn get_safe_arch<'a>(arch: &'a Arch, version: &Version) -> &'a Arch {
     use crate::system_info::{platform_arch, platform_name};
 
-    return match (platform_name(), platform_arch(), version) {
+    match (platform_name(), platform_arch(), version) {
         ("darwin", "arm64", Version::Semver(v)) if v.major < 16 => &Arch::X64,
         _ => arch,
-    };
+    }
 }
 
 #[cfg(windows)]
@@ -1,7 +1,154 @@
 pub mod extract;
+pub mod tar_gz;
 pub mod tar_xz;
+pub mod tar_zst;
 pub mod zip;
 
 pub use self::extract::{Error, Extract};
+pub use self::tar_gz::TarGz;
 pub use self::tar_xz::TarXz;
+pub use self::tar_zst::TarZst;
+#[cfg(windows)]
 pub use self::zip::Zip;
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// The archive formats Node.js releases and fnm bundles are published in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    #[cfg(unix)]
+    TarXz,
+    #[cfg(unix)]
+    TarGz,
+    /// Only used by fnm bundles, Node.js releases aren't published as `.tar.zst`
+    #[cfg(unix)]
+    TarZst,
+    #[cfg(windows)]
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// The formats that can be installed on this platform, the preferred one first.
+    /// Old releases and some mirrors only publish `.tar.gz` archives.
+    #[cfg(unix)]
+    pub fn supported() -> &'static [Self] {
+        &[Self::TarXz, Self::TarGz]
+    }
+
+    #[cfg(windows)]
+    pub fn supported() -> &'static [Self] {
+        &[Self::Zip]
+    }
+
+    /// Every format that can be extracted on this platform,
+    /// including the ones Node.js releases aren't published in
+    #[cfg(unix)]
+    pub fn all() -> &'static [Self] {
+        &[Self::TarXz, Self::TarGz, Self::TarZst]
+    }
+
+    #[cfg(windows)]
+    pub fn all() -> &'static [Self] {
+        Self::supported()
+    }
+
+    /// The format of the archive named `filename`, by its extension
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|format| filename.ends_with(&format!(".{}", format.extension())))
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            #[cfg(unix)]
+            Self::TarXz => "tar.xz",
+            #[cfg(unix)]
+            Self::TarGz => "tar.gz",
+            #[cfg(unix)]
+            Self::TarZst => "tar.zst",
+            #[cfg(windows)]
+            Self::Zip => "zip",
+        }
+    }
+
+    pub fn extract_into<P: AsRef<Path>>(self, response: impl Read, path: P) -> Result<(), Error> {
+        match self {
+            #[cfg(unix)]
+            Self::TarXz => TarXz::new(response).extract_into(path),
+            #[cfg(unix)]
+            Self::TarGz => TarGz::new(response).extract_into(path),
+            #[cfg(unix)]
+            Self::TarZst => TarZst::new(response).extract_into(path),
+            #[cfg(windows)]
+            Self::Zip => Zip::new(response).extract_into(path),
+        }
+    }
+
+    /// Archives the contents of `dir` into `file`, keeping symlinks as they are
+    pub fn archive_dir(self, dir: &Path, file: std::fs::File) -> Result<(), Error> {
+        match self {
+            #[cfg(unix)]
+            Self::TarXz => {
+                write_tar(dir, xz2::write::XzEncoder::new(file, 6))?.finish()?;
+            }
+            #[cfg(unix)]
+            Self::TarGz => {
+                let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+                write_tar(dir, encoder)?.finish()?;
+            }
+            #[cfg(unix)]
+            Self::TarZst => {
+                write_tar(dir, zstd::Encoder::new(file, 0)?)?.finish()?;
+            }
+            #[cfg(windows)]
+            Self::Zip => zip::archive_dir(dir, file)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn write_tar<W: Write>(dir: &Path, writer: W) -> std::io::Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    builder.follow_symlinks(false);
+    builder.append_dir_all(".", dir)?;
+    builder.into_inner()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_tar_zst_round_trip() {
+        let source = tempfile::tempdir().expect("Can't create a temp directory");
+        std::fs::create_dir(source.path().join("bin")).unwrap();
+        std::fs::write(source.path().join("bin").join("node"), "#!/bin/sh\n").unwrap();
+        std::os::unix::fs::symlink("node", source.path().join("bin").join("nodejs")).unwrap();
+
+        let archives = tempfile::tempdir().expect("Can't create a temp directory");
+        let archive_path = archives.path().join("bundle.tar.zst");
+        let format = ArchiveFormat::from_filename("bundle.tar.zst").unwrap();
+        assert_eq!(format, ArchiveFormat::TarZst);
+        format
+            .archive_dir(source.path(), std::fs::File::create(&archive_path).unwrap())
+            .expect("Can't archive the directory");
+
+        let target = tempfile::tempdir().expect("Can't create a temp directory");
+        format
+            .extract_into(std::fs::File::open(&archive_path).unwrap(), target.path())
+            .expect("Can't extract the archive");
+        assert_eq!(
+            std::fs::read_to_string(target.path().join("bin").join("node")).unwrap(),
+            "#!/bin/sh\n"
+        );
+        assert_eq!(
+            std::fs::read_link(target.path().join("bin").join("nodejs")).unwrap(),
+            Path::new("node")
+        );
+    }
+}
@@ -0,0 +1,22 @@
+use super::extract::{Error, Extract};
+use std::{io::Read, path::Path};
+
+pub struct TarZst<R: Read> {
+    response: R,
+}
+
+impl<R: Read> TarZst<R> {
+    #[allow(dead_code)]
+    pub fn new(response: R) -> Self {
+        Self { response }
+    }
+}
+
+impl<R: Read> Extract for TarZst<R> {
+    fn extract_into<P: AsRef<Path>>(self, path: P) -> Result<(), Error> {
+        let zst_stream = zstd::Decoder::new(self.response)?;
+        let mut tar_archive = tar::Archive::new(zst_stream);
+        tar_archive.unpack(&path)?;
+        Ok(())
+    }
+}
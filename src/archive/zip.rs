@@ -42,7 +42,7 @@ impl<R: Read> Extract for Zip<R> {
                 }
             }
 
-            if (&*file.name()).ends_with('/') {
+            if file.name().ends_with('/') {
                 debug!(
                     "File {} extracted to \"{}\"",
                     i,
@@ -58,7 +58,7 @@ impl<R: Read> Extract for Zip<R> {
                 );
                 if let Some(p) = outpath.parent() {
                     if !p.exists() {
-                        fs::create_dir_all(&p)?;
+                        fs::create_dir_all(p)?;
                     }
                 }
                 let mut outfile = fs::File::create(&outpath)?;
@@ -80,6 +80,33 @@ impl<R: Read> Extract for Zip<R> {
     }
 }
 
+/// Archives the contents of `dir` into a zip `file`
+#[cfg(windows)]
+pub fn archive_dir(dir: &Path, file: fs::File) -> Result<(), Error> {
+    let mut writer = zip::ZipWriter::new(file);
+    add_dir(&mut writer, dir, "")?;
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn add_dir(writer: &mut zip::ZipWriter<fs::File>, dir: &Path, prefix: &str) -> Result<(), Error> {
+    let options = zip::write::FileOptions::default();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            let dir_name = format!("{}/", name);
+            writer.add_directory(dir_name.as_str(), options)?;
+            add_dir(writer, &entry.path(), &dir_name)?;
+        } else {
+            writer.start_file(name, options)?;
+            io::copy(&mut fs::File::open(entry.path())?, writer)?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
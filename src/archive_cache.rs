@@ -0,0 +1,379 @@
+//! A content-addressed cache of downloaded Node.js archives.
+//!
+//! Every archive is stored as `<cache dir>/<sha256>/<file name>`, so a cached archive can be
+//! looked up by the checksum published in `SHASUMS256.txt` without trusting its file name.
+//! Next to the archive, a `.verification` file records how its checksum was verified,
+//! so reinstalling a verified archive doesn't need to fetch the checksums again.
+
+use log::debug;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const VERIFICATION_FILENAME: &str = ".verification";
+
+/// How the checksum of a cached archive was verified, from the weakest to the strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verification {
+    /// The archive was stored without comparing it to a published checksum
+    Unverified,
+    /// The archive matched the checksum in `SHASUMS256.txt`
+    Checksum,
+    /// The archive matched the checksum in a `SHASUMS256.txt` with a verified signature
+    Signature,
+}
+
+impl Verification {
+    fn read(checksum_dir: &Path) -> Self {
+        match std::fs::read_to_string(checksum_dir.join(VERIFICATION_FILENAME)) {
+            Ok(contents) if contents.trim() == "signature" => Self::Signature,
+            Ok(contents) if contents.trim() == "checksum" => Self::Checksum,
+            _ => Self::Unverified,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Unverified => "unverified",
+            Self::Checksum => "checksum",
+            Self::Signature => "signature",
+        }
+    }
+}
+
+/// The limits that pruning enforces on the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheLimits {
+    pub max_size: u64,
+    pub max_age: Duration,
+}
+
+#[derive(Debug)]
+pub struct CacheEntry {
+    path: PathBuf,
+    size: u64,
+    last_used: SystemTime,
+    verification: Verification,
+}
+
+impl CacheEntry {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn filename(&self) -> &str {
+        self.path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+    }
+
+    pub fn checksum(&self) -> &str {
+        self.path
+            .parent()
+            .and_then(Path::file_name)
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn last_used(&self) -> SystemTime {
+        self.last_used
+    }
+
+    pub fn verification(&self) -> Verification {
+        self.verification
+    }
+
+    fn remove(&self) -> std::io::Result<()> {
+        std::fs::remove_dir_all(self.path.parent().unwrap_or(&self.path))
+    }
+
+    fn mark_used(&self) {
+        if let Err(err) = touch(&self.path) {
+            debug!("Can't update the last usage of {:?}: {}", self.path, err);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ArchiveCache {
+    root: PathBuf,
+}
+
+impl ArchiveCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Lists the cached archives, most recently used first
+    pub fn entries(&self) -> std::io::Result<Vec<CacheEntry>> {
+        if !self.root.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut entries = vec![];
+        for checksum_dir in std::fs::read_dir(&self.root)? {
+            let checksum_dir = checksum_dir?;
+            if !checksum_dir.file_type()?.is_dir() || is_hidden(&checksum_dir.path()) {
+                continue;
+            }
+            let verification = Verification::read(&checksum_dir.path());
+            for file in std::fs::read_dir(checksum_dir.path())? {
+                let file = file?;
+                if is_hidden(&file.path()) {
+                    continue;
+                }
+                let metadata = file.metadata()?;
+                entries.push(CacheEntry {
+                    path: file.path(),
+                    size: metadata.len(),
+                    last_used: metadata.modified()?,
+                    verification,
+                });
+            }
+        }
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.last_used));
+        Ok(entries)
+    }
+
+    /// Finds a cached archive named `filename`. When `checksum` is known, only an archive
+    /// stored under that checksum is returned. Marks the found archive as recently used.
+    pub fn find(&self, filename: &str, checksum: Option<&str>) -> Option<CacheEntry> {
+        let entry = match checksum {
+            Some(checksum) => {
+                let path = self.root.join(checksum).join(filename);
+                let metadata = std::fs::metadata(&path)
+                    .ok()
+                    .filter(std::fs::Metadata::is_file)?;
+                CacheEntry {
+                    path,
+                    size: metadata.len(),
+                    last_used: metadata.modified().ok()?,
+                    verification: Verification::read(&self.root.join(checksum)),
+                }
+            }
+            None => self
+                .entries()
+                .ok()?
+                .into_iter()
+                .find(|entry| entry.filename() == filename)?,
+        };
+
+        entry.mark_used();
+        Some(entry)
+    }
+
+    /// Finds a cached archive named `filename` that was verified at least as strongly
+    /// as `verification`. Marks the found archive as recently used.
+    pub fn find_verified(&self, filename: &str, verification: Verification) -> Option<CacheEntry> {
+        let entry = self
+            .entries()
+            .ok()?
+            .into_iter()
+            .find(|entry| entry.filename() == filename && entry.verification >= verification)?;
+        entry.mark_used();
+        Some(entry)
+    }
+
+    /// Records that the archives stored under `checksum` were verified with `verification`,
+    /// unless they were already verified more strongly
+    pub fn set_verification(
+        &self,
+        checksum: &str,
+        verification: Verification,
+    ) -> std::io::Result<()> {
+        let checksum_dir = self.root.join(checksum);
+        if verification <= Verification::read(&checksum_dir) {
+            return Ok(());
+        }
+        std::fs::write(
+            checksum_dir.join(VERIFICATION_FILENAME),
+            verification.as_str(),
+        )
+    }
+
+    /// Moves the downloaded archive at `source` into the cache
+    pub fn insert(
+        &self,
+        filename: &str,
+        checksum: &str,
+        source: &Path,
+    ) -> std::io::Result<PathBuf> {
+        let target_dir = self.root.join(checksum);
+        std::fs::create_dir_all(&target_dir)?;
+        let target = target_dir.join(filename);
+        if std::fs::rename(source, &target).is_err() {
+            // The cache may be on a different filesystem than the downloads
+            std::fs::copy(source, &target)?;
+            std::fs::remove_file(source)?;
+        }
+        debug!("Stored {} in the cache", filename);
+        Ok(target)
+    }
+
+    pub fn remove(&self, path: &Path) -> std::io::Result<()> {
+        match self.entries()?.iter().find(|entry| entry.path == path) {
+            Some(entry) => entry.remove(),
+            None => Ok(()),
+        }
+    }
+
+    /// Removes archives that weren't used within `max_age`, and then the least recently
+    /// used archives until the cache fits in `max_size`. Returns the removed entries.
+    pub fn prune(&self, limits: &CacheLimits) -> std::io::Result<Vec<CacheEntry>> {
+        let now = SystemTime::now();
+        let mut total_size = 0;
+        let mut removed = vec![];
+
+        for entry in self.entries()? {
+            let age = now.duration_since(entry.last_used).unwrap_or_default();
+            if age > limits.max_age || total_size + entry.size > limits.max_size {
+                debug!("Pruning {:?} from the cache", entry.path);
+                entry.remove()?;
+                removed.push(entry);
+            } else {
+                total_size += entry.size;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Removes all the archives. Files next to them, like the stored index of versions, are kept.
+    pub fn clear(&self) -> std::io::Result<()> {
+        if !self.root.exists() {
+            return Ok(());
+        }
+        for entry in std::fs::read_dir(&self.root)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                std::fs::remove_dir_all(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+fn touch(path: &Path) -> std::io::Result<()> {
+    File::options()
+        .append(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn insert(cache: &ArchiveCache, filename: &str, checksum: &str, contents: &[u8]) -> PathBuf {
+        let download = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(download.path(), contents).unwrap();
+        let path = cache.insert(filename, checksum, download.path()).unwrap();
+        assert!(!download.path().exists());
+        path
+    }
+
+    fn set_last_used(path: &Path, ago: Duration) {
+        File::options()
+            .append(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - ago)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArchiveCache::new(dir.path());
+        let path = insert(&cache, "node-v14.0.0-linux-x64.tar.xz", "abcd", b"archive");
+
+        let find = |filename, checksum| cache.find(filename, checksum).map(|entry| entry.path);
+
+        assert_eq!(
+            find("node-v14.0.0-linux-x64.tar.xz", Some("abcd")),
+            Some(path.clone())
+        );
+        assert_eq!(find("node-v14.0.0-linux-x64.tar.xz", None), Some(path));
+        assert_eq!(find("node-v14.0.0-linux-x64.tar.xz", Some("ef01")), None);
+        assert_eq!(find("node-v16.0.0-linux-x64.tar.xz", None), None);
+    }
+
+    #[test]
+    fn test_find_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArchiveCache::new(dir.path());
+        let filename = "node-v14.0.0-linux-x64.tar.xz";
+        let path = insert(&cache, filename, "abcd", b"archive");
+
+        let find = |verification| {
+            cache
+                .find_verified(filename, verification)
+                .map(|entry| entry.path)
+        };
+
+        assert_eq!(find(Verification::Unverified), Some(path.clone()));
+        assert_eq!(find(Verification::Checksum), None);
+
+        cache
+            .set_verification("abcd", Verification::Signature)
+            .unwrap();
+        cache
+            .set_verification("abcd", Verification::Checksum)
+            .unwrap();
+        assert_eq!(find(Verification::Signature), Some(path));
+        assert_eq!(cache.entries().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_clear_keeps_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArchiveCache::new(dir.path());
+        insert(&cache, "node-v14.0.0-linux-x64.tar.xz", "abcd", b"archive");
+        std::fs::write(dir.path().join("index.json"), "[]").unwrap();
+
+        cache.clear().unwrap();
+
+        assert_eq!(cache.entries().unwrap().len(), 0);
+        assert!(dir.path().join("index.json").exists());
+    }
+
+    #[test]
+    fn test_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArchiveCache::new(dir.path());
+        let old = insert(&cache, "old.tar.xz", "0001", b"old");
+        let least_recent = insert(&cache, "least-recent.tar.xz", "0002", b"least recent");
+        let recent = insert(&cache, "recent.tar.xz", "0003", b"recent");
+        set_last_used(&old, Duration::from_hours(24 * 10));
+        set_last_used(&least_recent, Duration::from_hours(1));
+
+        let removed = cache
+            .prune(&CacheLimits {
+                max_size: 10,
+                max_age: Duration::from_hours(24),
+            })
+            .unwrap();
+
+        let removed: Vec<_> = removed.iter().map(CacheEntry::filename).collect();
+        assert_eq!(removed, vec!["least-recent.tar.xz", "old.tar.xz"]);
+        let remaining: Vec<_> = cache
+            .entries()
+            .unwrap()
+            .into_iter()
+            .map(|x| x.path)
+            .collect();
+        assert_eq!(remaining, vec![recent]);
+    }
+}
@@ -0,0 +1,286 @@
+//! Bundles of installed Node versions and their aliases, to set up fnm on machines
+//! that can't reach a mirror, like air-gapped ones.
+//!
+//! A bundle is an archive with the same layout as the fnm directory:
+//! `node-versions/vX/installation` for every version, and a manifest listing the versions
+//! and aliases it contains.
+
+use crate::alias::{create_alias, list_aliases};
+use crate::archive::{self, ArchiveFormat};
+use crate::config::FnmConfig;
+use crate::directory_portal::DirectoryPortal;
+use crate::downloader::installed_version;
+use crate::version::Version;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILENAME: &str = "fnm-bundle.json";
+const VERSIONS_DIR: &str = "node-versions";
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Manifest {
+    /// The platform the installations run on, like `linux`
+    pub platform: String,
+    pub versions: Vec<String>,
+    /// The aliases pointing to the bundled versions, by name
+    pub aliases: BTreeMap<String, String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// What installing a bundle changed
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Installed {
+    pub versions: Vec<Version>,
+    /// The versions that were installed already, so they were kept as they are
+    pub skipped_versions: Vec<Version>,
+    pub aliases: Vec<(String, Version)>,
+    /// The aliases that exist already, so they were kept as they are
+    pub skipped_aliases: Vec<String>,
+}
+
+/// Bundles the installed `versions` and the aliases pointing to them into `path`.
+/// The archive format comes from the extension of `path`.
+pub fn create(config: &FnmConfig, versions: &[Version], path: &Path) -> Result<Manifest, Error> {
+    let format = archive_format(path)?;
+    let installations_dir = config.installations_dir();
+    let temp_installations_dir = installations_dir.join(".downloads");
+    std::fs::create_dir_all(&temp_installations_dir).context(IoError)?;
+
+    // The bundle is laid out in a staging directory first. It is in the installations
+    // directory, so the files can be hard linked into it instead of copied.
+    let staging = tempfile::TempDir::new_in(&temp_installations_dir).context(IoError)?;
+    for version in versions {
+        let version_dir = installations_dir.join(version.v_str());
+        crate::fs::link_or_copy_dir(
+            &version_dir,
+            &staging.path().join(VERSIONS_DIR).join(version.v_str()),
+            true,
+        )
+        .context(IoError)?;
+    }
+
+    let version_names: Vec<String> = versions.iter().map(Version::v_str).collect();
+    let aliases = list_aliases(config)
+        .context(IoError)?
+        .into_iter()
+        .filter(|alias| version_names.iter().any(|name| name == alias.s_ver()))
+        .map(|alias| (alias.name().to_string(), alias.s_ver().to_string()))
+        .collect();
+    let manifest = Manifest {
+        platform: crate::system_info::platform_name().to_string(),
+        versions: version_names,
+        aliases,
+        created_at: chrono::Utc::now(),
+    };
+    let json = serde_json::to_vec_pretty(&manifest).context(InvalidManifest)?;
+    std::fs::write(staging.path().join(MANIFEST_FILENAME), json).context(IoError)?;
+
+    // Written next to `path` and renamed, so a failure doesn't leave a partial bundle behind
+    let parent_dir = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let bundle = tempfile::NamedTempFile::new_in(parent_dir).context(IoError)?;
+    let file = bundle.reopen().context(IoError)?;
+    format
+        .archive_dir(staging.path(), file)
+        .context(CantArchive)?;
+    bundle
+        .persist(path)
+        .map_err(|err| err.error)
+        .context(IoError)?;
+
+    Ok(manifest)
+}
+
+/// Installs the versions and aliases of the bundle at `path`.
+///
+/// Every version is validated by running its `node --version` before any of them is
+/// installed, so a bundle made for another platform doesn't install anything.
+pub fn install(config: &FnmConfig, path: &Path) -> Result<Installed, Error> {
+    let format = archive_format(path)?;
+    let installations_dir = config.installations_dir();
+    let temp_installations_dir = installations_dir.join(".downloads");
+    std::fs::create_dir_all(&temp_installations_dir).context(IoError)?;
+
+    let extracted = tempfile::TempDir::new_in(&temp_installations_dir).context(IoError)?;
+    let file = std::fs::File::open(path).context(IoError)?;
+    format
+        .extract_into(file, extracted.path())
+        .context(CantExtract)?;
+
+    let manifest_path = extracted.path().join(MANIFEST_FILENAME);
+    let manifest = std::fs::read(&manifest_path)
+        .ok()
+        .context(MissingManifest { path })?;
+    let manifest: Manifest = serde_json::from_slice(&manifest).context(InvalidManifest)?;
+    let platform = crate::system_info::platform_name();
+    ensure!(
+        manifest.platform == platform,
+        WrongPlatform {
+            bundle_platform: manifest.platform,
+            platform,
+        }
+    );
+
+    let mut installed = Installed::default();
+    let mut to_install = vec![];
+    for name in &manifest.versions {
+        let version = Version::parse(name).context(InvalidVersion { name })?;
+        if installations_dir.join(version.v_str()).exists() {
+            installed.skipped_versions.push(version);
+            continue;
+        }
+        let installation_dir = extracted
+            .path()
+            .join(VERSIONS_DIR)
+            .join(version.v_str())
+            .join("installation");
+        let actual_version = installed_version(&installation_dir);
+        debug!("{:?} reports {:?}", installation_dir, actual_version);
+        ensure!(
+            actual_version.as_ref() == Some(&version),
+            BrokenInstallation { version }
+        );
+        to_install.push((version, installation_dir));
+    }
+
+    for (version, installation_dir) in to_install {
+        let portal = DirectoryPortal::new_in(
+            &temp_installations_dir,
+            installations_dir.join(version.v_str()),
+        );
+        std::fs::rename(&installation_dir, portal.join("installation")).context(IoError)?;
+        portal.teleport().context(IoError)?;
+        installed.versions.push(version);
+    }
+
+    let existing_aliases = list_aliases(config).context(IoError)?;
+    for (name, version) in &manifest.aliases {
+        if existing_aliases.iter().any(|alias| alias.name() == name) {
+            installed.skipped_aliases.push(name.clone());
+            continue;
+        }
+        let version = Version::parse(version).context(InvalidVersion { name: version })?;
+        create_alias(config, name, &version).context(IoError)?;
+        installed.aliases.push((name.clone(), version));
+    }
+
+    Ok(installed)
+}
+
+fn archive_format(path: &Path) -> Result<ArchiveFormat, Error> {
+    path.file_name()
+        .and_then(|name| ArchiveFormat::from_filename(&name.to_string_lossy()))
+        .context(UnsupportedFormat { path })
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("{}", source))]
+    IoError { source: std::io::Error },
+    #[snafu(display(
+        "Can't tell the archive format of {}. Supported extensions: {}",
+        path.display(),
+        crate::downloader::supported_extensions()
+    ))]
+    UnsupportedFormat { path: PathBuf },
+    #[snafu(display("Can't create the bundle: {}", source))]
+    CantArchive { source: archive::Error },
+    #[snafu(display("Can't extract the bundle: {}", source))]
+    CantExtract { source: archive::Error },
+    #[snafu(display("{} is not an fnm bundle, it has no {}", path.display(), MANIFEST_FILENAME))]
+    MissingManifest { path: PathBuf },
+    #[snafu(display("Invalid bundle manifest: {}", source))]
+    InvalidManifest { source: serde_json::Error },
+    #[snafu(display("Invalid version {:?} in the bundle: {}", name, source))]
+    InvalidVersion { name: String, source: semver::Error },
+    #[snafu(display(
+        "The bundle is made for {}, and can't be installed on {}",
+        bundle_platform,
+        platform
+    ))]
+    WrongPlatform {
+        bundle_platform: String,
+        platform: &'static str,
+    },
+    #[snafu(display("Node {} in the bundle doesn't run, so nothing was installed", version))]
+    BrokenInstallation { version: Version },
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::importer::tests::create_fake_installation;
+    use pretty_assertions::assert_eq;
+
+    fn config_in(dir: &Path) -> FnmConfig {
+        FnmConfig::default().with_base_dir(Some(dir.to_path_buf()))
+    }
+
+    fn install_fake_version(config: &FnmConfig, version: &str) -> Version {
+        let version = Version::parse(version).unwrap();
+        create_fake_installation(&version.installation_path(config), version.v_str().as_str());
+        version
+    }
+
+    #[test]
+    fn test_create_and_install() {
+        let golden_dir = tempfile::tempdir().unwrap();
+        let golden = config_in(golden_dir.path());
+        let v16 = install_fake_version(&golden, "v16.20.0");
+        let v18 = install_fake_version(&golden, "v18.12.1");
+        install_fake_version(&golden, "v20.0.0");
+        create_alias(&golden, "default", &v18).unwrap();
+        create_alias(&golden, "old", &v16).unwrap();
+        let bundle_path = golden_dir.path().join("bundle.tar.xz");
+
+        let manifest = create(&golden, &[v16.clone(), v18.clone()], &bundle_path).unwrap();
+        assert_eq!(manifest.versions, vec!["v16.20.0", "v18.12.1"]);
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = config_in(target_dir.path());
+        install_fake_version(&target, "v16.20.0");
+        create_alias(&target, "old", &Version::parse("v16.20.0").unwrap()).unwrap();
+
+        let installed = install(&target, &bundle_path).unwrap();
+        assert_eq!(
+            installed,
+            Installed {
+                versions: vec![v18.clone()],
+                skipped_versions: vec![v16],
+                aliases: vec![("default".to_string(), v18.clone())],
+                skipped_aliases: vec!["old".to_string()],
+            }
+        );
+        assert_eq!(
+            installed_version(&v18.installation_path(&target)),
+            Some(v18)
+        );
+    }
+
+    #[test]
+    fn test_broken_installation_installs_nothing() {
+        let golden_dir = tempfile::tempdir().unwrap();
+        let golden = config_in(golden_dir.path());
+        let v16 = install_fake_version(&golden, "v16.20.0");
+        let v18 = Version::parse("v18.12.1").unwrap();
+        create_fake_installation(&v18.installation_path(&golden), "v18.0.0");
+        let bundle_path = golden_dir.path().join("bundle.tar.gz");
+        create(&golden, &[v16, v18.clone()], &bundle_path).unwrap();
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = config_in(target_dir.path());
+        match install(&target, &bundle_path) {
+            Err(Error::BrokenInstallation { version }) => assert_eq!(version, v18),
+            other => panic!("Expected a broken installation, got {:?}", other),
+        }
+        assert_eq!(
+            crate::installed_versions::list(target.installations_dir()).unwrap(),
+            Vec::<Version>::new()
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use ring::digest::{Context, SHA256};
+use std::fmt::Write;
+use std::io::Read;
+
+/// A reader that computes the SHA-256 digest of everything read through it,
+/// so archives can be verified while they are being streamed into extraction.
+pub struct Sha256Reader<R: Read> {
+    inner: R,
+    context: Context,
+}
+
+impl<R: Read> Sha256Reader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            context: Context::new(&SHA256),
+        }
+    }
+
+    /// The lowercase hex digest of the bytes read so far
+    pub fn hex_digest(self) -> String {
+        self.context
+            .finish()
+            .as_ref()
+            .iter()
+            .fold(String::new(), |mut digest, byte| {
+                write!(digest, "{:02x}", byte).expect("Can't write to a string");
+                digest
+            })
+    }
+}
+
+impl<R: Read> Read for Sha256Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read_bytes = self.inner.read(buf)?;
+        self.context.update(&buf[..read_bytes]);
+        Ok(read_bytes)
+    }
+}
+
+/// Finds the checksum of `filename` in the contents of a `SHASUMS256.txt` file,
+/// which is formatted like the output of `sha256sum`: `<hex digest>  <file name>`
+pub fn find_checksum<'a>(shasums: &'a str, filename: &str) -> Option<&'a str> {
+    shasums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let checksum = parts.next()?;
+        let name = parts.next()?;
+        (name.trim_start_matches('*') == filename).then_some(checksum)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_sha256_reader() {
+        let mut reader = Sha256Reader::new("hello world".as_bytes());
+        std::io::copy(&mut reader, &mut std::io::sink()).unwrap();
+        assert_eq!(
+            reader.hex_digest(),
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        );
+    }
+
+    #[test]
+    fn test_find_checksum() {
+        let shasums = indoc::indoc! {"
+            0f8b5e5a0a1e4bd1c4e3a8e6e1e5b4ba2f1a1c2a3a9e1ad1d5b9d5e5c1b2a3f4  node-v12.0.0-darwin-x64.tar.gz
+            b1b1e4d4d4fd2e3e1e1b5e6a3d55ab5a34a1ed8ab9e8c5b5b1b1e4d4d4fd2e3e  node-v12.0.0-linux-x64.tar.xz
+            c2c2e4d4d4fd2e3e1e1b5e6a3d55ab5a34a1ed8ab9e8c5b5b1b1e4d4d4fd2e3e *node-v12.0.0-win-x64.zip
+        "};
+        assert_eq!(
+            find_checksum(shasums, "node-v12.0.0-linux-x64.tar.xz"),
+            Some("b1b1e4d4d4fd2e3e1e1b5e6a3d55ab5a34a1ed8ab9e8c5b5b1b1e4d4d4fd2e3e")
+        );
+        assert_eq!(
+            find_checksum(shasums, "node-v12.0.0-win-x64.zip"),
+            Some("c2c2e4d4d4fd2e3e1e1b5e6a3d55ab5a34a1ed8ab9e8c5b5b1b1e4d4d4fd2e3e")
+        );
+        assert_eq!(
+            find_checksum(shasums, "node-v12.0.0-linux-arm64.tar.xz"),
+            None
+        );
+    }
+}
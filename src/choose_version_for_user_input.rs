@@ -1,6 +1,8 @@
 use crate::config::FnmConfig;
 use crate::fs;
 use crate::installed_versions;
+use crate::lts::LtsType;
+use crate::remote_node_index;
 use crate::system_version;
 use crate::user_version::UserVersion;
 use crate::version::Version;
@@ -41,6 +43,8 @@ pub fn choose_version_for_user_input<'a>(
             path: system_version::path(),
             version: Version::Bypassed,
         })
+    } else if let Some(version) = installed_lts_version(requested_version, &all_versions, config) {
+        Some(applicable_installation(version, config))
     } else if let Some(alias_name) = requested_version.alias_name() {
         let alias_path = config.aliases_dir().join(&alias_name);
         let system_path = system_version::path();
@@ -69,23 +73,45 @@ pub fn choose_version_for_user_input<'a>(
         }
     } else {
         let current_version = requested_version.to_version(&all_versions, config);
-        current_version.map(|version| {
-            info!("Using Node {}", version.to_string().cyan());
-            let path = config
-                .installations_dir()
-                .join(version.to_string())
-                .join("installation");
-
-            ApplicableVersion {
-                path,
-                version: version.clone(),
-            }
-        })
+        current_version.map(|version| applicable_installation(version.clone(), config))
     };
 
     Ok(result)
 }
 
+fn applicable_installation(version: Version, config: &FnmConfig) -> ApplicableVersion {
+    info!("Using Node {}", version.to_string().cyan());
+    let path = config
+        .installations_dir()
+        .join(version.to_string())
+        .join("installation");
+
+    ApplicableVersion { path, version }
+}
+
+/// The installed version of an LTS line given by its code name, like `lts/argon`, or relative
+/// to the latest one, like `lts/-1`. The LTS lines are read from the last fetched index,
+/// so the version doesn't need the alias `fnm install` creates for the line.
+pub fn installed_lts_version(
+    requested_version: &UserVersion,
+    installed: &[Version],
+    config: &FnmConfig,
+) -> Option<Version> {
+    let UserVersion::Full(Version::Lts(lts_type @ (LtsType::CodeName(_) | LtsType::Offset(_)))) =
+        requested_version
+    else {
+        return None;
+    };
+    let index = match remote_node_index::list_offline(&config.node_index_path()) {
+        Ok(index) => index,
+        Err(err) => {
+            info!("Can't resolve lts/{} with the index: {}", lts_type, err);
+            return None;
+        }
+    };
+    lts_type.pick_installed(&index, installed).cloned()
+}
+
 #[derive(Debug, Snafu)]
 pub enum Error {
     #[snafu(display("Can't find requested version: {}", requested_version))]
@@ -21,6 +21,13 @@ pub enum SubCommand {
     #[structopt(name = "use")]
     Use(commands::r#use::Use),
 
+    /// Write the Node.js version of the project to its version file, and use it
+    ///
+    /// The version is written to the first of `--version-files` in the current directory,
+    /// like `.nvmrc`, so `fnm use` and the use-on-cd hooks pick it up.
+    #[structopt(name = "pin", visible_aliases = &["local"])]
+    Pin(commands::pin::Pin),
+
     /// Print and set up required environment variables for fnm
     ///
     /// This command generates a series of shell commands that
@@ -54,6 +61,10 @@ pub enum SubCommand {
     #[structopt(name = "current")]
     Current(commands::current::Current),
 
+    /// Print the metadata of a Node.js version, such as its npm and V8 versions
+    #[structopt(name = "info")]
+    Info(commands::info::Info),
+
     /// Run a command within fnm context
     ///
     /// Example:
@@ -67,8 +78,26 @@ pub enum SubCommand {
     ///
     /// > Warning: when providing an alias, it will remove the Node version the alias
     /// is pointing to, along with the other aliases that point to the same version.
+    #[allow(clippy::doc_lazy_continuation)]
     #[structopt(name = "uninstall")]
     Uninstall(commands::uninstall::Uninstall),
+
+    /// Manage the cache of downloaded Node.js archives
+    #[structopt(name = "cache")]
+    Cache(commands::cache::Cache),
+
+    /// Import the Node.js installations of another version manager
+    ///
+    /// Supports nvm, n, volta and asdf. The aliases of nvm are imported as well.
+    #[structopt(name = "import")]
+    Import(commands::import::Import),
+
+    /// Bundle installed Node.js versions to install them on other machines
+    ///
+    /// Bundles don't need access to a mirror to be installed, so they can set up
+    /// machines without internet access.
+    #[structopt(name = "bundle")]
+    Bundle(commands::bundle::Bundle),
 }
 
 impl SubCommand {
@@ -79,13 +108,18 @@ impl SubCommand {
             Self::Install(cmd) => cmd.call(config),
             Self::Env(cmd) => cmd.call(config),
             Self::Use(cmd) => cmd.call(config),
+            Self::Pin(cmd) => cmd.call(config),
             Self::Completions(cmd) => cmd.call(config),
             Self::Alias(cmd) => cmd.call(config),
             Self::Default(cmd) => cmd.call(config),
             Self::Current(cmd) => cmd.call(config),
+            Self::Info(cmd) => cmd.call(config),
             Self::Exec(cmd) => cmd.call(config),
             Self::Uninstall(cmd) => cmd.call(config),
             Self::Unalias(cmd) => cmd.call(config),
+            Self::Cache(cmd) => cmd.call(config),
+            Self::Import(cmd) => cmd.call(config),
+            Self::Bundle(cmd) => cmd.call(config),
         }
     }
 }
@@ -4,7 +4,6 @@ use crate::choose_version_for_user_input::{
     choose_version_for_user_input, Error as ApplicableVersionError,
 };
 use crate::config::FnmConfig;
-use crate::installed_versions;
 use crate::user_version::UserVersion;
 use snafu::{OptionExt, ResultExt, Snafu};
 use structopt::StructOpt;
@@ -36,8 +35,6 @@ impl Command for Alias {
 pub enum Error {
     #[snafu(display("Can't create symlink for alias: {}", source))]
     CantCreateSymlink { source: std::io::Error },
-    #[snafu(display("Can't list local installed versions: {}", source))]
-    VersionListingError { source: installed_versions::Error },
     #[snafu(display("Version {} not found locally", version))]
     VersionNotFound { version: UserVersion },
     #[snafu(display("{}", source))]
@@ -0,0 +1,126 @@
+use super::command::Command;
+use crate::bundle;
+use crate::config::FnmConfig;
+use crate::installed_versions;
+use crate::outln;
+use crate::user_version::UserVersion;
+use crate::version::Version;
+use colored::Colorize;
+use snafu::{OptionExt, ResultExt, Snafu};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub enum Bundle {
+    /// Bundle installed Node.js versions and the aliases pointing to them into an archive
+    ///
+    /// The archive format comes from the extension of the output file, like `.tar.zst` or `.tar.xz`.
+    #[structopt(name = "create")]
+    Create {
+        /// The versions to bundle. Bundles all the installed versions when missing.
+        versions: Vec<UserVersion>,
+        /// The path of the bundle to create
+        #[structopt(short, long, parse(from_os_str))]
+        output: PathBuf,
+    },
+
+    /// Install the Node.js versions and aliases of a bundle
+    ///
+    /// Versions and aliases that exist already are kept as they are.
+    #[structopt(name = "install")]
+    Install {
+        /// The path of the bundle
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+}
+
+impl Command for Bundle {
+    type Error = Error;
+
+    fn apply(self, config: &FnmConfig) -> Result<(), Self::Error> {
+        match self {
+            Self::Create { versions, output } => {
+                let versions = resolve_versions(config, &versions)?;
+                let manifest =
+                    bundle::create(config, &versions, &output).context(CantCreateBundle)?;
+                outln!(
+                    config,
+                    Info,
+                    "Bundled {} and {} aliases into {}",
+                    manifest.versions.join(", ").cyan(),
+                    manifest.aliases.len(),
+                    output.display()
+                );
+            }
+            Self::Install { path } => {
+                let installed = bundle::install(config, &path).context(CantInstallBundle)?;
+                for version in &installed.skipped_versions {
+                    outln!(
+                        config,
+                        Info,
+                        "Skipping {}, as it is already installed",
+                        version.v_str().cyan()
+                    );
+                }
+                for version in &installed.versions {
+                    outln!(config, Info, "Installed {}", version.v_str().cyan());
+                }
+                for name in &installed.skipped_aliases {
+                    outln!(
+                        config,
+                        Info,
+                        "Skipping the alias {}, as it already exists",
+                        name.cyan()
+                    );
+                }
+                for (name, version) in &installed.aliases {
+                    outln!(
+                        config,
+                        Info,
+                        "Aliased {} -> {}",
+                        name.cyan(),
+                        version.v_str().cyan()
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The installed versions matching `requested`, or all of them when nothing is requested
+fn resolve_versions(config: &FnmConfig, requested: &[UserVersion]) -> Result<Vec<Version>, Error> {
+    let mut installed =
+        installed_versions::list(config.installations_dir()).context(CantListInstalled)?;
+    installed.sort();
+    if requested.is_empty() {
+        return Ok(installed);
+    }
+
+    let mut versions = vec![];
+    for user_version in requested {
+        let version = user_version
+            .to_version(&installed, config)
+            .context(VersionNotInstalled {
+                version: user_version.clone(),
+            })?;
+        if !versions.contains(version) {
+            versions.push(version.clone());
+        }
+    }
+    Ok(versions)
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Can't list the installed versions: {}", source))]
+    CantListInstalled { source: installed_versions::Error },
+    #[snafu(display("Can't find an installed Node version matching {}", version))]
+    VersionNotInstalled { version: UserVersion },
+    #[snafu(display("Can't create the bundle: {}", source))]
+    CantCreateBundle { source: bundle::Error },
+    #[snafu(display("Can't install the bundle: {}", source))]
+    CantInstallBundle { source: bundle::Error },
+}
@@ -0,0 +1,92 @@
+use super::command::Command;
+use crate::archive_cache::{ArchiveCache, CacheEntry};
+use crate::config::FnmConfig;
+use crate::outln;
+use colored::Colorize;
+use snafu::{ResultExt, Snafu};
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub enum Cache {
+    /// List the cached Node.js archives
+    #[structopt(name = "list", visible_aliases = &["ls"])]
+    List,
+
+    /// Remove the archives that exceed `--cache-max-size` or `--cache-max-age`
+    #[structopt(name = "prune")]
+    Prune,
+
+    /// Remove all the cached archives
+    #[structopt(name = "clear")]
+    Clear {
+        /// Also remove the stored index of Node.js versions,
+        /// which is needed to resolve versions when offline
+        #[structopt(long)]
+        index: bool,
+    },
+}
+
+impl Command for Cache {
+    type Error = Error;
+
+    fn apply(self, config: &FnmConfig) -> Result<(), Self::Error> {
+        let cache = ArchiveCache::new(config.cache_dir());
+
+        match self {
+            Self::List => {
+                let entries = cache.entries().context(CantReadCache)?;
+                for entry in &entries {
+                    println!("* {}", describe(entry));
+                }
+                let total_size: u64 = entries.iter().map(CacheEntry::size).sum();
+                outln!(
+                    config,
+                    Info,
+                    "{}",
+                    format!("Total: {}", format_size(total_size)).dimmed()
+                );
+            }
+            Self::Prune => {
+                let removed = cache.prune(&config.cache_limits()).context(CantReadCache)?;
+                for entry in &removed {
+                    outln!(config, Info, "Removed {}", entry.filename().cyan());
+                }
+            }
+            Self::Clear { index } => {
+                cache.clear().context(CantClearCache)?;
+                if index {
+                    config
+                        .node_index_cache(false)
+                        .clear()
+                        .context(CantClearCache)?;
+                }
+                outln!(config, Info, "Cleared the cache");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn describe(entry: &CacheEntry) -> String {
+    let last_used: chrono::DateTime<chrono::Local> = entry.last_used().into();
+    let details = format!(
+        "{}, last used {}",
+        format_size(entry.size()),
+        last_used.format("%Y-%m-%d")
+    );
+    format!("{} {}", entry.filename(), details.dimmed())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Can't read the cache: {}", source))]
+    CantReadCache { source: std::io::Error },
+    #[snafu(display("Can't clear the cache: {}", source))]
+    CantClearCache { source: std::io::Error },
+}
@@ -1,6 +1,7 @@
 use super::command::Command;
 use crate::config::FnmConfig;
 use crate::fs::symlink_dir;
+use crate::http::without_credentials;
 use crate::outln;
 use crate::path_ext::PathExt;
 use crate::shell::{infer_shell, Shell, AVAILABLE_SHELLS};
@@ -8,6 +9,7 @@ use colored::Colorize;
 use snafu::{OptionExt, Snafu};
 use std::fmt::Debug;
 use structopt::StructOpt;
+use url::Url;
 
 #[derive(StructOpt, Debug, Default)]
 pub struct Env {
@@ -78,6 +80,19 @@ impl Command for Env {
                 config.version_file_strategy().as_str()
             )
         );
+        let version_sources: Vec<_> = config
+            .version_sources()
+            .iter()
+            .map(|source| source.as_str())
+            .collect();
+        println!(
+            "{}",
+            shell.set_env_var("FNM_VERSION_SOURCES", &version_sources.join(","))
+        );
+        println!(
+            "{}",
+            shell.set_env_var("FNM_VERSION_FILES", &config.version_files().join(","))
+        );
         println!(
             "{}",
             shell.set_env_var("FNM_DIR", config.base_dir_with_default().to_str().unwrap())
@@ -86,9 +101,30 @@ impl Command for Env {
             "{}",
             shell.set_env_var("FNM_LOGLEVEL", config.log_level().clone().into())
         );
+        let node_dist_mirrors: Vec<_> = config
+            .node_dist_mirror
+            .iter()
+            .map(without_credentials)
+            .collect();
+        if node_dist_mirrors != config.node_dist_mirror {
+            outln!(
+                config,
+                Error,
+                "{} The credentials in the mirror URL are not exported. Use {} or a netrc file instead.",
+                "warning:".yellow().bold(),
+                "FNM_NODE_DIST_MIRROR_TOKEN".italic()
+            );
+        }
         println!(
             "{}",
-            shell.set_env_var("FNM_NODE_DIST_MIRROR", config.node_dist_mirror.as_str())
+            shell.set_env_var(
+                "FNM_NODE_DIST_MIRROR",
+                &node_dist_mirrors
+                    .iter()
+                    .map(Url::as_str)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
         );
         println!(
             "{}",
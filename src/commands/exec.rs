@@ -66,7 +66,7 @@ impl Cmd for Exec {
             std::env::join_paths(paths).context(CantAddPathToEnvironment)?
         };
 
-        let exit_status = Command::new(&binary)
+        let exit_status = Command::new(binary)
             .args(arguments)
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
@@ -0,0 +1,217 @@
+use super::command::Command;
+use crate::alias::create_alias;
+use crate::config::FnmConfig;
+use crate::importer::{self, Source};
+use crate::installed_versions;
+use crate::outln;
+use crate::user_version::UserVersion;
+use crate::version::Version;
+use colored::Colorize;
+use snafu::{OptionExt, ResultExt, Snafu};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub struct Import {
+    /// The version manager to import the installations from
+    #[structopt(long, possible_values = Source::possible_values())]
+    from: Source,
+
+    /// The directory of the version manager, like `~/.nvm`.
+    /// Defaults to the directory the version manager uses, like `$NVM_DIR`.
+    ///
+    /// With `--from path`, a directory of Node installations, or a single installation.
+    #[structopt(parse(from_os_str))]
+    dir: Option<PathBuf>,
+
+    /// Copy the installations, instead of hard linking their files when possible
+    #[structopt(long)]
+    copy: bool,
+}
+
+impl Command for Import {
+    type Error = Error;
+
+    fn apply(self, config: &FnmConfig) -> Result<(), Self::Error> {
+        let dir = self
+            .dir
+            .or_else(|| self.from.default_dir())
+            .context(MissingDirectory { from: self.from })?;
+        let installations =
+            importer::discover(self.from, &dir).context(CantReadDirectory { path: &dir })?;
+        let installations_dir = config.installations_dir();
+
+        for installation in installations {
+            let path = installation.path;
+            let Some(version) = installation.version else {
+                outln!(
+                    config,
+                    Error,
+                    "{} Skipping {}, as its node can't run",
+                    "warning:".yellow().bold(),
+                    path.display()
+                );
+                continue;
+            };
+            if installations_dir.join(version.v_str()).exists() {
+                outln!(
+                    config,
+                    Info,
+                    "Skipping {}, as it is already installed",
+                    version.v_str().cyan()
+                );
+                continue;
+            }
+
+            importer::import(&path, &version, &installations_dir, !self.copy).context(
+                CantImport {
+                    path: &path,
+                    version: version.clone(),
+                },
+            )?;
+            outln!(
+                config,
+                Info,
+                "Imported Node {} from {}",
+                version.v_str().cyan(),
+                path.display()
+            );
+        }
+
+        if self.from == Source::Nvm {
+            import_nvm_aliases(config, &dir)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates the aliases of nvm that point to an installed version, unless fnm has them already
+fn import_nvm_aliases(config: &FnmConfig, dir: &std::path::Path) -> Result<(), Error> {
+    let aliases = match importer::nvm_aliases(dir) {
+        Ok(aliases) => aliases,
+        Err(err) => {
+            log::debug!("Can't read the aliases of nvm: {}", err);
+            return Ok(());
+        }
+    };
+    let installed: Vec<Version> =
+        installed_versions::list(config.installations_dir()).context(CantListInstalled)?;
+    let existing = crate::alias::list_aliases(config).context(CantListAliases)?;
+
+    for (name, target) in aliases {
+        if existing.iter().any(|alias| alias.name() == name) {
+            outln!(
+                config,
+                Info,
+                "Skipping the alias {}, as it already exists",
+                name.cyan()
+            );
+            continue;
+        }
+
+        let version = target
+            .parse::<UserVersion>()
+            .ok()
+            .and_then(|user_version| user_version.to_version(&installed, config));
+        let Some(version) = version else {
+            outln!(
+                config,
+                Error,
+                "{} Skipping the alias {}, as no installed version matches {}",
+                "warning:".yellow().bold(),
+                name.cyan(),
+                target
+            );
+            continue;
+        };
+
+        create_alias(config, &name, version).context(CantCreateAlias)?;
+        outln!(
+            config,
+            Info,
+            "Imported the alias {} -> {}",
+            name.cyan(),
+            version.v_str().cyan()
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Can't find the directory of {}, please provide it", from))]
+    MissingDirectory { from: Source },
+    #[snafu(display("Can't read the installations in {}: {}", path.display(), source))]
+    CantReadDirectory {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Can't import {} from {}: {}", version, path.display(), source))]
+    CantImport {
+        path: PathBuf,
+        version: Version,
+        source: std::io::Error,
+    },
+    #[snafu(display("Can't list the installed versions: {}", source))]
+    CantListInstalled { source: installed_versions::Error },
+    #[snafu(display("Can't list the aliases: {}", source))]
+    CantListAliases { source: std::io::Error },
+    #[snafu(display("Can't create the alias: {}", source))]
+    CantCreateAlias { source: std::io::Error },
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::importer::tests::create_fake_installation;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_import_from_nvm() {
+        let base_dir = tempfile::tempdir().unwrap();
+        let config = FnmConfig::default().with_base_dir(Some(base_dir.path().to_path_buf()));
+        let nvm_dir = tempfile::tempdir().unwrap();
+        let versions_dir = nvm_dir.path().join("versions").join("node");
+        create_fake_installation(&versions_dir.join("v16.20.0"), "v16.20.0");
+        create_fake_installation(&versions_dir.join("v18.12.1"), "v18.12.1");
+        let aliases_dir = nvm_dir.path().join("alias");
+        std::fs::create_dir_all(&aliases_dir).unwrap();
+        std::fs::write(aliases_dir.join("default"), "node\n").unwrap();
+        std::fs::write(aliases_dir.join("old"), "16\n").unwrap();
+        std::fs::write(aliases_dir.join("future"), "20\n").unwrap();
+
+        Import {
+            from: Source::Nvm,
+            dir: Some(nvm_dir.path().to_path_buf()),
+            copy: false,
+        }
+        .apply(&config)
+        .expect("Can't import");
+
+        let mut installed = installed_versions::list(config.installations_dir()).unwrap();
+        installed.sort();
+        assert_eq!(
+            installed,
+            vec![
+                Version::parse("v16.20.0").unwrap(),
+                Version::parse("v18.12.1").unwrap(),
+            ]
+        );
+
+        let mut aliases: Vec<_> = crate::alias::list_aliases(&config)
+            .unwrap()
+            .iter()
+            .map(|alias| (alias.name().to_string(), alias.s_ver().to_string()))
+            .collect();
+        aliases.sort();
+        assert_eq!(
+            aliases,
+            vec![
+                ("default".to_string(), "v18.12.1".to_string()),
+                ("old".to_string(), "v16.20.0".to_string()),
+            ]
+        );
+    }
+}
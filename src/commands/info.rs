@@ -0,0 +1,152 @@
+use super::command::Command;
+use crate::config::FnmConfig;
+use crate::installed_versions;
+use crate::remote_node_index::{self, IndexedNodeVersion};
+use crate::user_version::UserVersion;
+use crate::version::Version;
+use log::debug;
+use snafu::{OptionExt, ResultExt, Snafu};
+use std::fmt::Write;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub struct Info {
+    /// An installed version, an alias, or a version from the remote index,
+    /// e.g. `16`, `default` or `lts/gallium`
+    version: UserVersion,
+}
+
+impl Command for Info {
+    type Error = Error;
+
+    fn apply(self, config: &FnmConfig) -> Result<(), Self::Error> {
+        let installed =
+            installed_versions::list(config.installations_dir()).context(CantListLocalVersions)?;
+        let index = if config.offline() {
+            remote_node_index::list_offline(&config.node_index_path())
+        } else {
+            remote_node_index::list(&config.node_dist_mirrors(), &config.node_index_cache(false))
+        };
+
+        let local_version = match &self.version {
+            UserVersion::Full(Version::Lts(_)) | UserVersion::Latest => None,
+            version => version.to_version(&installed, config).cloned(),
+        };
+
+        let (version, indexed) = match (local_version, index) {
+            (Some(version), Ok(index)) => {
+                let indexed = index.into_iter().find(|x| x.version == version);
+                (version, indexed)
+            }
+            (Some(version), Err(err)) => {
+                debug!("Can't list the remote versions: {}", err);
+                (version, None)
+            }
+            (None, index) => {
+                let index = index.context(CantListRemoteVersions)?;
+                let indexed = find_in_index(&self.version, index, config).with_context(|| {
+                    VersionNotFound {
+                        requested_version: self.version.clone(),
+                    }
+                })?;
+                (indexed.version.clone(), Some(indexed))
+            }
+        };
+
+        let is_installed = installed.contains(&version);
+        print!("{}", describe(&version, indexed.as_ref(), is_installed));
+        Ok(())
+    }
+}
+
+pub(crate) fn find_in_index(
+    requested_version: &UserVersion,
+    index: Vec<IndexedNodeVersion>,
+    config: &FnmConfig,
+) -> Option<IndexedNodeVersion> {
+    let version = match requested_version {
+        UserVersion::Full(Version::Lts(lts_type)) => lts_type.pick_latest(&index)?.version.clone(),
+        requested_version => requested_version
+            .to_version(index.iter().map(|x| &x.version), config)?
+            .clone(),
+    };
+    index.into_iter().find(|x| x.version == version)
+}
+
+fn describe(version: &Version, indexed: Option<&IndexedNodeVersion>, is_installed: bool) -> String {
+    let mut title = version.v_str();
+    if let Some(lts) = indexed.and_then(|x| x.lts.as_ref()) {
+        title = format!("{} ({})", title, lts);
+    }
+
+    let yes_no = |value: bool| if value { "yes" } else { "no" }.to_string();
+    let mut rows = vec![("Installed", yes_no(is_installed))];
+    if let Some(indexed) = indexed {
+        rows.push(("Released", indexed.date.to_string()));
+        let fields = [
+            ("npm", &indexed.npm),
+            ("V8", &indexed.v8),
+            ("libuv", &indexed.uv),
+            ("zlib", &indexed.zlib),
+            ("OpenSSL", &indexed.openssl),
+            ("Modules", &indexed.modules),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                rows.push((name, value.clone()));
+            }
+        }
+        rows.push(("Security", yes_no(indexed.security)));
+    }
+
+    let mut description = format!("{}\n", title);
+    for (name, value) in rows {
+        writeln!(description, "  {:<10} {}", format!("{}:", name), value).unwrap();
+    }
+    description
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Can't list the installed versions: {}", source))]
+    CantListLocalVersions { source: installed_versions::Error },
+    #[snafu(display("Can't list the remote versions: {}", source))]
+    CantListRemoteVersions { source: remote_node_index::Error },
+    #[snafu(display(
+        "Can't find a Node version that matches {} locally or in remote",
+        requested_version
+    ))]
+    VersionNotFound { requested_version: UserVersion },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_describe() {
+        let indexed = IndexedNodeVersion {
+            version: Version::parse("v16.13.0").unwrap(),
+            lts: Some("Gallium".to_string()),
+            date: "2021-10-26".parse().unwrap(),
+            files: vec![],
+            npm: Some("8.1.0".to_string()),
+            v8: Some("9.4.146.19".to_string()),
+            uv: None,
+            zlib: None,
+            openssl: Some("1.1.1l+quic".to_string()),
+            modules: Some("93".to_string()),
+            security: true,
+        };
+
+        assert_eq!(
+            describe(&indexed.version, Some(&indexed), false),
+            "v16.13.0 (Gallium)\n  Installed: no\n  Released:  2021-10-26\n  npm:       8.1.0\n  V8:        9.4.146.19\n  OpenSSL:   1.1.1l+quic\n  Modules:   93\n  Security:  yes\n"
+        );
+        assert_eq!(
+            describe(&indexed.version, None, true),
+            "v16.13.0\n  Installed: yes\n"
+        );
+    }
+}
@@ -1,17 +1,30 @@
 use crate::alias::create_alias;
 use crate::arch::get_safe_arch;
+use crate::archive::ArchiveFormat;
+use crate::archive_cache::ArchiveCache;
 use crate::config::FnmConfig;
-use crate::downloader::{install_node_dist, Error as DownloaderError};
+use crate::downloader::{
+    filename_for_version, install_node_archive, install_node_archive_from_url, install_node_dist,
+    install_node_dist_offline, install_node_source, version_from_filename, ArchiveOrigin,
+    Error as DownloaderError,
+};
+use crate::http::without_credentials;
+use crate::libc::Libc;
+use crate::log_level::LogLevel;
 use crate::lts::LtsType;
 use crate::outln;
-use crate::remote_node_index;
+use crate::progress;
+use crate::remote_node_index::{self, IndexCache, IndexedNodeVersion};
+use crate::source_build::{self, BuildOptions};
 use crate::user_version::UserVersion;
 use crate::version::Version;
 use crate::version_files::get_user_version_for_directory;
 use colored::Colorize;
 use log::debug;
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use std::path::PathBuf;
 use structopt::StructOpt;
+use url::Url;
 
 #[derive(StructOpt, Debug, Default)]
 pub struct Install {
@@ -21,6 +34,35 @@ pub struct Install {
     /// Install latest LTS
     #[structopt(long, conflicts_with = "version")]
     pub lts: bool,
+
+    /// Fetch the index of Node.js versions from the mirror, even if the cached one isn't expired
+    #[structopt(long)]
+    pub refresh: bool,
+
+    /// Install a local Node archive, like a custom build, instead of a version from the mirror
+    #[structopt(
+        long,
+        parse(from_os_str),
+        conflicts_with_all = &["version", "lts", "from-url"]
+    )]
+    pub from_file: Option<PathBuf>,
+
+    /// Install the Node archive at this URL, instead of a version from the mirror
+    #[structopt(long, conflicts_with_all = &["version", "lts"])]
+    pub from_url: Option<Url>,
+
+    /// Build the version from its source code, for platforms without prebuilt binaries.
+    /// Requires the toolchain to build Node: a C++ compiler, `make` and Python.
+    #[structopt(long, conflicts_with_all = &["from-file", "from-url"])]
+    pub from_source: bool,
+
+    /// The number of parallel jobs when building from source. Defaults to the number of CPUs.
+    #[structopt(long, env = "FNM_BUILD_JOBS")]
+    pub jobs: Option<usize>,
+
+    /// Additional flags for `./configure` when building from source, separated by spaces
+    #[structopt(long, env = "FNM_CONFIGURE_FLAGS", allow_hyphen_values = true)]
+    pub configure_flags: Option<String>,
 }
 
 impl Install {
@@ -29,92 +71,140 @@ impl Install {
             Self {
                 version: Some(_),
                 lts: true,
+                ..
             } => Err(Error::TooManyVersionsProvided),
             Self {
                 version: v,
                 lts: false,
+                ..
             } => Ok(v),
             Self {
                 version: None,
                 lts: true,
+                ..
             } => Ok(Some(UserVersion::Full(Version::Lts(LtsType::Latest)))),
         }
     }
 }
 
+impl Install {
+    /// Installs the archive given in `--from-file` or `--from-url`, if any
+    fn install_archive(&self, config: &FnmConfig) -> Result<Option<Version>, Error> {
+        let installation = if let Some(path) = &self.from_file {
+            outln!(config, Info, "Installing {}", path.display());
+            install_node_archive(path, config.installations_dir())
+        } else if let Some(url) = &self.from_url {
+            outln!(config, Info, "Installing {}", without_credentials(url));
+            install_node_archive_from_url(
+                url,
+                config.installations_dir(),
+                progress::is_enabled(config.log_level()),
+            )
+        } else {
+            return Ok(None);
+        };
+        installation.context(DownloadError).map(Some)
+    }
+
+    /// How to build the version, when it's built from source
+    fn build_options(&self, config: &FnmConfig) -> Option<BuildOptions> {
+        if !self.from_source {
+            return None;
+        }
+        Some(BuildOptions {
+            jobs: self.jobs.unwrap_or_else(source_build::default_jobs),
+            configure_flags: self
+                .configure_flags
+                .iter()
+                .flat_map(|flags| flags.split_whitespace())
+                .map(String::from)
+                .collect(),
+            show_output: config.log_level().is_writable(&LogLevel::Info),
+        })
+    }
+}
+
 impl super::command::Command for Install {
     type Error = Error;
 
     fn apply(self, config: &FnmConfig) -> Result<(), Self::Error> {
+        if let Some(version) = self.install_archive(config)? {
+            outln!(
+                config,
+                Info,
+                "Installed {}",
+                format!("Node {}", version).cyan()
+            );
+            return set_default_version(config, &version);
+        }
+
         let current_dir = std::env::current_dir().unwrap();
+        let index_cache = config.node_index_cache(self.refresh);
+        let build_options = self.build_options(config);
+        ensure!(
+            build_options.is_none() || cfg!(unix),
+            CantBuildFromSourceOnThisPlatform
+        );
+        ensure!(
+            build_options.is_none() || !config.offline(),
+            CantBuildFromSourceOffline
+        );
 
         let current_version = self
             .version()?
             .or_else(|| get_user_version_for_directory(current_dir, config))
             .context(CantInferVersion)?;
 
-        let version = match current_version.clone() {
-            UserVersion::Full(Version::Semver(actual_version)) => Version::Semver(actual_version),
-            UserVersion::Full(v @ (Version::Bypassed | Version::Alias(_))) => {
-                ensure!(false, UninstallableVersion { version: v });
-                unreachable!();
-            }
-            UserVersion::Full(Version::Lts(lts_type)) => {
-                let available_versions: Vec<_> = remote_node_index::list(&config.node_dist_mirror)
-                    .context(CantListRemoteVersions)?;
-                let picked_version = lts_type
-                    .pick_latest(&available_versions)
-                    .with_context(|| CantFindRelevantLts {
-                        lts_type: lts_type.clone(),
-                    })?
-                    .version
-                    .clone();
-                debug!(
-                    "Resolved {} into Node version {}",
-                    Version::Lts(lts_type).v_str().cyan(),
-                    picked_version.v_str().cyan()
-                );
-                picked_version
-            }
-            current_version => {
-                let available_versions: Vec<_> = remote_node_index::list(&config.node_dist_mirror)
-                    .context(CantListRemoteVersions)?
-                    .drain(..)
-                    .map(|x| x.version)
-                    .collect();
-
-                current_version
-                    .to_version(&available_versions, config)
-                    .context(CantFindNodeVersion {
-                        requested_version: current_version,
-                    })?
-                    .clone()
-            }
+        let offline_versions = if config.offline() {
+            Some(offline_versions(config))
+        } else {
+            None
         };
-
-        // Automatically swap Apple Silicon to x64 arch for appropriate versions.
-        let safe_arch = get_safe_arch(&config.arch, &version);
-
-        let version_str = format!("Node {}", &version);
-        outln!(
+        let version = resolve_version(
+            &current_version,
             config,
-            Info,
-            "Installing {} ({})",
-            version_str.cyan(),
-            safe_arch.to_string()
-        );
+            &index_cache,
+            offline_versions.as_deref(),
+            build_options.is_some(),
+        )?;
 
-        match install_node_dist(
-            &version,
-            &config.node_dist_mirror,
-            config.installations_dir(),
-            safe_arch,
-        ) {
+        let version_str = format!("Node {}", &version);
+        let cache = config.archive_cache();
+        let installation = if let Some(build_options) = &build_options {
+            outln!(config, Info, "Building {} from source", version_str.cyan());
+            install_node_source(
+                &version,
+                config.node_source_mirrors(),
+                config.installations_dir(),
+                &config.archive_verification(),
+                cache.as_ref(),
+                build_options,
+                progress::is_enabled(config.log_level()),
+            )
+        } else {
+            install_node_build(config, &version, offline_versions.is_some(), cache.as_ref())
+        };
+        match installation {
             Err(err @ DownloaderError::VersionAlreadyInstalled { .. }) => {
                 outln!(config, Error, "{} {}", "warning:".bold().yellow(), err);
             }
-            other_err => other_err.context(DownloadError)?,
-        };
+            other => {
+                let origin = other.context(DownloadError)?;
+                outln!(
+                    config,
+                    Info,
+                    "Installed {} from {}",
+                    version_str.cyan(),
+                    origin
+                );
+            }
+        }
+
+        if let Some(cache) = cache {
+            if let Err(err) = cache.prune(&config.cache_limits()) {
+                debug!("Can't prune the cache: {}", err);
+            }
+        }
 
         if let UserVersion::Full(Version::Lts(lts_type)) = current_version {
             let alias_name = Version::Lts(lts_type).v_str();
@@ -126,12 +216,237 @@ impl super::command::Command for Install {
             create_alias(config, &alias_name, &version).context(IoError)?;
         }
 
-        if !config.default_version_dir().exists() {
-            debug!("Tagging {} as the default version", version.v_str().cyan());
-            create_alias(config, "default", &version).context(IoError)?;
+        set_default_version(config, &version)
+    }
+}
+
+/// Installs the prebuilt binaries of `version`, from the mirror or from the cache when offline
+fn install_node_build(
+    config: &FnmConfig,
+    version: &Version,
+    offline: bool,
+    cache: Option<&ArchiveCache>,
+) -> Result<ArchiveOrigin, DownloaderError> {
+    // Automatically swap Apple Silicon to x64 arch for appropriate versions.
+    let safe_arch = get_safe_arch(&config.arch, version);
+    let libc = config.libc();
+    outln!(
+        config,
+        Info,
+        "Installing {} ({}{})",
+        format!("Node {}", version).cyan(),
+        safe_arch,
+        libc.build_suffix()
+    );
+
+    match cache {
+        Some(cache) if offline => install_node_dist_offline(
+            version,
+            config.installations_dir(),
+            safe_arch,
+            libc,
+            &config.archive_verification(),
+            cache,
+        )
+        .map(|()| ArchiveOrigin::Cache),
+        _ => install_node_dist(
+            version,
+            &config.node_dist_mirrors(),
+            config.installations_dir(),
+            safe_arch,
+            libc,
+            &config.archive_verification(),
+            cache,
+            progress::is_enabled(config.log_level()),
+        ),
+    }
+}
+
+/// Makes `version` the default version, when it's the first one installed
+fn set_default_version(config: &FnmConfig, version: &Version) -> Result<(), Error> {
+    if !config.default_version_dir().exists() {
+        debug!("Tagging {} as the default version", version.v_str().cyan());
+        create_alias(config, "default", version).context(IoError)?;
+    }
+    Ok(())
+}
+
+/// Resolves the requested version into a specific version that can be installed
+fn resolve_version(
+    current_version: &UserVersion,
+    config: &FnmConfig,
+    index_cache: &IndexCache,
+    offline_versions: Option<&[Version]>,
+    from_source: bool,
+) -> Result<Version, Error> {
+    // Every release publishes its source, so any version can be built from source
+    let buildable = |versions: &[IndexedNodeVersion]| {
+        if from_source {
+            versions.to_vec()
+        } else {
+            with_builds_for_arch(versions, config)
+        }
+    };
+    let not_available_offline = |available: &[Version]| CantFindOfflineVersion {
+        requested_version: current_version.clone(),
+        available: available.to_vec(),
+    };
+
+    let no_build_for_arch = |newest: &IndexedNodeVersion| NoBuildForArch {
+        requested_version: current_version.clone(),
+        arch: get_safe_arch(&config.arch, &newest.version).clone(),
+        libc: config.libc(),
+        newest_version: newest.version.v_str(),
+        available_arches: newest.available_arches(config.libc()).join(", "),
+    };
+
+    let version = match current_version.clone() {
+        UserVersion::Full(Version::Semver(actual_version)) => {
+            let version = Version::Semver(actual_version);
+            if let Some(offline_versions) = offline_versions {
+                ensure!(
+                    offline_versions.contains(&version),
+                    not_available_offline(offline_versions)
+                );
+            }
+            version
         }
+        UserVersion::Full(Version::Alias(alias)) if alias == "iojs" => {
+            return IoJsNotSupported.fail();
+        }
+        UserVersion::Full(v @ (Version::Bypassed | Version::Alias(_))) => {
+            ensure!(false, UninstallableVersion { version: v });
+            unreachable!();
+        }
+        UserVersion::Full(Version::Lts(lts_type)) => {
+            let available_versions =
+                list_installable_versions(config, index_cache, offline_versions, from_source)?;
+            let buildable_versions = buildable(&available_versions);
+            let picked = lts_type.pick_latest(&buildable_versions);
+            let picked_version = match (picked, offline_versions) {
+                (Some(picked), _) => picked.version.clone(),
+                (None, Some(offline_versions)) => {
+                    return not_available_offline(offline_versions).fail();
+                }
+                (None, None) => {
+                    let newest = lts_type.pick_latest(&available_versions).with_context(|| {
+                        CantFindRelevantLts {
+                            lts_type: lts_type.clone(),
+                        }
+                    })?;
+                    return no_build_for_arch(newest).fail();
+                }
+            };
+            debug!(
+                "Resolved {} into Node version {}",
+                Version::Lts(lts_type).v_str().cyan(),
+                picked_version.v_str().cyan()
+            );
+            picked_version
+        }
+        current_version => {
+            if let Some(offline_versions) = offline_versions {
+                return current_version
+                    .to_version(offline_versions, config)
+                    .cloned()
+                    .with_context(|| not_available_offline(offline_versions));
+            }
+
+            let available_versions =
+                list_installable_versions(config, index_cache, None, from_source)?;
+            let buildable_versions = buildable(&available_versions);
+            let picked =
+                current_version.to_version(buildable_versions.iter().map(|x| &x.version), config);
+            if let Some(version) = picked {
+                return Ok(version.clone());
+            }
 
-        Ok(())
+            // Tell apart versions that don't exist from versions without a build for this arch
+            let newest = available_versions
+                .iter()
+                .rfind(|x| current_version.matches(&x.version, config))
+                .with_context(|| CantFindNodeVersion {
+                    requested_version: current_version.clone(),
+                })?;
+            return no_build_for_arch(newest).fail();
+        }
+    };
+    Ok(version)
+}
+
+/// The versions that have a build for the configured arch and libc on the current platform
+fn with_builds_for_arch(
+    versions: &[IndexedNodeVersion],
+    config: &FnmConfig,
+) -> Vec<IndexedNodeVersion> {
+    versions
+        .iter()
+        .filter(|x| x.has_build_for(get_safe_arch(&config.arch, &x.version), config.libc()))
+        .cloned()
+        .collect()
+}
+
+/// Lists the versions that can be installed: the ones in the remote index, or when offline,
+/// the ones in the last fetched index that have a cached archive.
+/// With `from_source`, the index is fetched from the mirrors the source code is downloaded from.
+fn list_installable_versions(
+    config: &FnmConfig,
+    index_cache: &IndexCache,
+    offline_versions: Option<&[Version]>,
+    from_source: bool,
+) -> Result<Vec<IndexedNodeVersion>, Error> {
+    let mirrors = if from_source {
+        config.node_source_mirrors().to_vec()
+    } else {
+        config.node_dist_mirrors()
+    };
+    match offline_versions {
+        None => remote_node_index::list(&mirrors, index_cache).context(CantListRemoteVersions),
+        Some(offline_versions) => {
+            let mut versions = remote_node_index::list_offline(&index_cache.path)
+                .context(CantListRemoteVersions)?;
+            versions.retain(|x| offline_versions.contains(&x.version));
+            Ok(versions)
+        }
+    }
+}
+
+/// The versions that have an archive for the current platform in the cache,
+/// verified as required by the config
+fn offline_versions(config: &FnmConfig) -> Vec<Version> {
+    let required_verification = config.archive_verification().required_level();
+    let entries = match config.archive_cache().map(|cache| cache.entries()) {
+        Some(Ok(entries)) => entries,
+        Some(Err(err)) => {
+            debug!("Can't read the cache: {}", err);
+            vec![]
+        }
+        None => vec![],
+    };
+
+    let mut versions: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry.verification() >= required_verification)
+        .filter_map(|entry| {
+            let version = version_from_filename(entry.filename())?;
+            let arch = get_safe_arch(&config.arch, &version);
+            let is_installable = ArchiveFormat::supported().iter().any(|format| {
+                filename_for_version(&version, arch, config.libc(), *format) == entry.filename()
+            });
+            is_installable.then_some(version)
+        })
+        .collect();
+    versions.sort();
+    versions.dedup();
+    versions
+}
+
+fn describe_offline_versions(versions: &[Version]) -> String {
+    if versions.is_empty() {
+        "There are no cached versions.".to_string()
+    } else {
+        let versions: Vec<_> = versions.iter().map(Version::v_str).collect();
+        format!("Versions available offline: {}", versions.join(", "))
     }
 }
 
@@ -150,7 +465,7 @@ pub enum Error {
     CantInferVersion,
     #[snafu(display("Having a hard time listing the remote versions: {}", source))]
     CantListRemoteVersions {
-        source: crate::http::Error,
+        source: remote_node_index::Error,
     },
     #[snafu(display(
         "Can't find a Node version that matches {} in remote",
@@ -159,6 +474,30 @@ pub enum Error {
     CantFindNodeVersion {
         requested_version: UserVersion,
     },
+    #[snafu(display(
+        "Can't find a cached version that matches {}, and fnm is offline.\n{}",
+        requested_version,
+        describe_offline_versions(available)
+    ))]
+    CantFindOfflineVersion {
+        requested_version: UserVersion,
+        available: Vec<Version>,
+    },
+    #[snafu(display(
+        "Can't find a version that matches {} with a build for {}{}. The latest matching version, {}, is only built for: {}.\nYou can try a different `--arch`.",
+        requested_version,
+        arch,
+        libc.build_suffix(),
+        newest_version,
+        available_arches
+    ))]
+    NoBuildForArch {
+        requested_version: UserVersion,
+        arch: crate::arch::Arch,
+        libc: Libc,
+        newest_version: String,
+        available_arches: String,
+    },
     #[snafu(display("Can't find relevant LTS named {}", lts_type))]
     CantFindRelevantLts {
         lts_type: crate::lts::LtsType,
@@ -167,8 +506,14 @@ pub enum Error {
     UninstallableVersion {
         version: Version,
     },
+    #[snafu(display("io.js versions can't be installed with fnm, only Node.js versions"))]
+    IoJsNotSupported,
     #[snafu(display("Too many versions provided. Please don't use --lts with a version string."))]
     TooManyVersionsProvided,
+    #[snafu(display("Can't build from source offline, as the source code isn't cached."))]
+    CantBuildFromSourceOffline,
+    #[snafu(display("Building Node from source is only supported on Linux and macOS"))]
+    CantBuildFromSourceOnThisPlatform,
 }
 
 #[cfg(test)]
@@ -181,12 +526,16 @@ mod tests {
     #[test]
     fn test_set_default_on_new_installation() {
         let base_dir = tempfile::tempdir().unwrap();
-        let config = FnmConfig::default().with_base_dir(Some(base_dir.path().to_path_buf()));
+        let config = FnmConfig::default()
+            .with_base_dir(Some(base_dir.path().to_path_buf()))
+            .with_libc(Libc::Glibc);
         assert!(!config.default_version_dir().exists());
 
         Install {
             version: UserVersion::from_str("12.0.0").ok(),
             lts: false,
+            refresh: false,
+            ..Install::default()
         }
         .apply(&config)
         .expect("Can't install");
@@ -202,4 +551,161 @@ mod tests {
                 .ok()
         );
     }
+
+    #[cfg(not(unix))]
+    #[test]
+    fn test_from_source_is_unsupported() {
+        let base_dir = tempfile::tempdir().unwrap();
+        let config = FnmConfig::default().with_base_dir(Some(base_dir.path().to_path_buf()));
+        let result = Install {
+            version: UserVersion::from_str("12.0.0").ok(),
+            from_source: true,
+            ..Install::default()
+        }
+        .apply(&config);
+        assert!(matches!(
+            result,
+            Err(Error::CantBuildFromSourceOnThisPlatform)
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_offline() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        let files = [remote_node_index::build_name(
+            &crate::arch::Arch::X64,
+            Libc::Glibc,
+        )];
+        mirror.write_file(
+            "index.json",
+            serde_json::json!([
+                { "version": "v16.0.0", "lts": false, "date": "2021-04-20", "files": files },
+                { "version": "v14.0.0", "lts": "Fermium", "date": "2020-04-21", "files": files },
+            ])
+            .to_string(),
+        );
+        let base_dir = tempfile::tempdir().unwrap();
+        let mut config = FnmConfig::default()
+            .with_base_dir(Some(base_dir.path().to_path_buf()))
+            .with_libc(Libc::Glibc);
+        config.node_dist_mirror = vec![mirror.url().clone()];
+        config.arch = crate::arch::Arch::X64;
+        let install = |config: &FnmConfig, version: &str| {
+            Install {
+                version: UserVersion::from_str(version).ok(),
+                lts: false,
+                refresh: false,
+                ..Install::default()
+            }
+            .apply(config)
+        };
+
+        install(&config, "14").expect("Can't install online");
+        std::fs::remove_dir_all(config.installations_dir().join("v14.0.0")).unwrap();
+
+        let config = config.with_offline(true);
+        install(&config, "lts/fermium").expect("Can't install offline");
+        assert!(config.installations_dir().join("v14.0.0").exists());
+
+        let err = install(&config, "16").expect_err("Installed a version that isn't cached");
+        assert_eq!(
+            err.to_string(),
+            "Can't find a cached version that matches v16.x.x, and fnm is offline.\nVersions available offline: v14.0.0"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_version_with_build_for_arch() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        let x64 = remote_node_index::build_name(&crate::arch::Arch::X64, Libc::Glibc);
+        let arm64 = remote_node_index::build_name(&crate::arch::Arch::Arm64, Libc::Glibc);
+        let x64_musl = remote_node_index::build_name(&crate::arch::Arch::X64, Libc::Musl);
+        let arm64_musl = remote_node_index::build_name(&crate::arch::Arch::Arm64, Libc::Musl);
+        mirror.write_file(
+            "index.json",
+            serde_json::json!([
+                { "version": "v14.1.0", "lts": false, "date": "2020-04-29", "files": [&x64, &arm64, &x64_musl] },
+                { "version": "v14.2.0", "lts": false, "date": "2020-05-05", "files": [&x64, &arm64_musl] },
+            ])
+            .to_string(),
+        );
+        let index_dir = tempfile::tempdir().unwrap();
+        let index_cache = IndexCache {
+            path: index_dir.path().join("index.json"),
+            ttl: std::time::Duration::ZERO,
+            refresh: false,
+        };
+        let resolve_for = |arch: crate::arch::Arch, libc: Libc| {
+            let mut config = FnmConfig::default().with_libc(libc);
+            config.node_dist_mirror = vec![mirror.url().clone()];
+            config.arch = arch;
+            let requested_version = UserVersion::from_str("14").unwrap();
+            resolve_version(&requested_version, &config, &index_cache, None, false)
+        };
+        let resolve = |arch: crate::arch::Arch| resolve_for(arch, Libc::Glibc);
+
+        let x64_version = resolve(crate::arch::Arch::X64).unwrap();
+        assert_eq!(x64_version.v_str(), "v14.2.0");
+
+        let arm64_version = resolve(crate::arch::Arch::Arm64).unwrap();
+        assert_eq!(arm64_version.v_str(), "v14.1.0");
+
+        let err = resolve(crate::arch::Arch::S390x).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Can't find a version that matches v14.x.x with a build for s390x. The latest matching version, v14.2.0, is only built for: x64.\nYou can try a different `--arch`."
+        );
+
+        let musl_version = resolve_for(crate::arch::Arch::X64, Libc::Musl).unwrap();
+        assert_eq!(musl_version.v_str(), "v14.1.0");
+
+        let err = resolve_for(crate::arch::Arch::S390x, Libc::Musl).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Can't find a version that matches v14.x.x with a build for s390x-musl. The latest matching version, v14.2.0, is only built for: arm64.\nYou can try a different `--arch`."
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_nvm_aliases() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        let x64 = remote_node_index::build_name(&crate::arch::Arch::X64, Libc::Glibc);
+        mirror.write_file(
+            "index.json",
+            serde_json::json!([
+                { "version": "v19.9.0", "lts": false, "date": "2023-04-10", "files": [&x64] },
+                { "version": "v18.16.0", "lts": "Hydrogen", "date": "2023-04-12", "files": [&x64] },
+                { "version": "v16.20.0", "lts": "Gallium", "date": "2023-03-28", "files": [&x64] },
+                { "version": "v4.9.1", "lts": "Argon", "date": "2018-03-29", "files": [&x64] },
+            ])
+            .to_string(),
+        );
+        let index_dir = tempfile::tempdir().unwrap();
+        let index_cache = IndexCache {
+            path: index_dir.path().join("index.json"),
+            ttl: std::time::Duration::ZERO,
+            refresh: false,
+        };
+        let mut config = FnmConfig::default().with_libc(Libc::Glibc);
+        config.node_dist_mirror = vec![mirror.url().clone()];
+        config.arch = crate::arch::Arch::X64;
+        let resolve = |version: &str| {
+            let requested_version = UserVersion::from_str(version).unwrap();
+            resolve_version(&requested_version, &config, &index_cache, None, false)
+                .map(|version| version.v_str())
+        };
+
+        assert_eq!(resolve("node").unwrap(), "v19.9.0");
+        assert_eq!(resolve("stable").unwrap(), "v19.9.0");
+        assert_eq!(resolve("lts/-1").unwrap(), "v16.20.0");
+        assert_eq!(resolve("lts/argon").unwrap(), "v4.9.1");
+        assert!(matches!(
+            resolve("iojs").unwrap_err(),
+            Error::IoJsNotSupported
+        ));
+    }
 }
@@ -1,18 +1,133 @@
 use crate::config::FnmConfig;
-use crate::remote_node_index;
+use crate::remote_node_index::{self, IndexedNodeVersion};
+use crate::user_version::UserVersion;
+use crate::version::Version;
 use snafu::{ResultExt, Snafu};
+use std::str::FromStr;
 use structopt::StructOpt;
 
-#[derive(StructOpt, Debug)]
-pub struct LsRemote {}
+#[derive(StructOpt, Debug, Default)]
+pub struct LsRemote {
+    /// Only show versions matching this version string, e.g. `18` or `16.13`
+    version: Option<UserVersion>,
+
+    /// Only show LTS versions. Use `--lts=<codename>` to show the versions of a specific LTS line
+    #[structopt(long, min_values = 0, require_equals = true)]
+    #[allow(clippy::option_option)]
+    lts: Option<Option<String>>,
+
+    /// Only show the latest version of every major version
+    #[structopt(long)]
+    latest: bool,
+
+    /// Only show versions released on or after this date, formatted as YYYY-MM-DD
+    #[structopt(long)]
+    since: Option<chrono::NaiveDate>,
+
+    /// The order of the printed versions
+    #[structopt(
+        long,
+        default_value = "asc",
+        possible_values = SortingMethod::possible_values()
+    )]
+    sort: SortingMethod,
+
+    /// Print the versions as JSON, including their release date and available files
+    #[structopt(long)]
+    json: bool,
+
+    /// Fetch the index of Node.js versions from the mirror, even if the cached one isn't expired
+    #[structopt(long)]
+    refresh: bool,
+}
+
+impl LsRemote {
+    fn filter(
+        &self,
+        versions: Vec<IndexedNodeVersion>,
+        config: &FnmConfig,
+    ) -> Vec<IndexedNodeVersion> {
+        let mut versions: Vec<_> = versions
+            .into_iter()
+            .filter(|x| match &self.lts {
+                None => true,
+                Some(None) => x.lts.is_some(),
+                Some(Some(codename)) => x
+                    .lts
+                    .as_ref()
+                    .is_some_and(|lts| lts.eq_ignore_ascii_case(codename)),
+            })
+            .filter(|x| {
+                self.version
+                    .as_ref()
+                    .is_none_or(|version| version.matches(&x.version, config))
+            })
+            .filter(|x| self.since.is_none_or(|since| x.date >= since))
+            .collect();
+
+        if self.latest {
+            // The index is sorted, so the latest version of a major is the last one of its run
+            let mut latest: Vec<IndexedNodeVersion> = vec![];
+            for version in versions {
+                match latest.last_mut() {
+                    Some(last) if major(&last.version) == major(&version.version) => {
+                        *last = version;
+                    }
+                    _ => latest.push(version),
+                }
+            }
+            versions = latest;
+        }
+
+        if let SortingMethod::Descending = self.sort {
+            versions.reverse();
+        }
+
+        versions
+    }
+}
+
+fn major(version: &Version) -> Option<u64> {
+    match version {
+        Version::Semver(semver) => Some(semver.major),
+        _ => None,
+    }
+}
 
 impl super::command::Command for LsRemote {
     type Error = Error;
 
     fn apply(self, config: &FnmConfig) -> Result<(), Self::Error> {
-        let all_versions = remote_node_index::list(&config.node_dist_mirror).context(HttpError)?;
+        let all_versions = if config.offline() {
+            remote_node_index::list_offline(&config.node_index_path())
+        } else {
+            remote_node_index::list(
+                &config.node_dist_mirrors(),
+                &config.node_index_cache(self.refresh),
+            )
+        }
+        .context(CantListRemoteVersions)?;
+
+        let versions = self.filter(all_versions, config);
+
+        if self.json {
+            let json: Vec<_> = versions
+                .iter()
+                .map(|version| {
+                    serde_json::json!({
+                        "version": version.version.v_str(),
+                        "lts": version.lts,
+                        "date": version.date,
+                        "files": version.files,
+                    })
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&json).context(CantSerializeVersions)?;
+            println!("{}", json);
+            return Ok(());
+        }
 
-        for version in all_versions {
+        for version in versions {
             print!("{}", version.version);
             if let Some(lts) = &version.lts {
                 print!(" ({})", lts);
@@ -24,7 +139,116 @@ impl super::command::Command for LsRemote {
     }
 }
 
+#[derive(Debug, Default)]
+pub enum SortingMethod {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortingMethod {
+    pub fn possible_values() -> &'static [&'static str] {
+        &["asc", "desc"]
+    }
+}
+
+impl FromStr for SortingMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "asc" => Ok(SortingMethod::Ascending),
+            "desc" => Ok(SortingMethod::Descending),
+            _ => Err(format!(
+                "Invalid sorting method: {}. Expected one of: asc, desc",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Snafu)]
 pub enum Error {
-    HttpError { source: crate::http::Error },
+    #[snafu(display("{}", source))]
+    CantListRemoteVersions { source: remote_node_index::Error },
+    #[snafu(display("Can't serialize the versions: {}", source))]
+    CantSerializeVersions { source: serde_json::Error },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn index() -> Vec<IndexedNodeVersion> {
+        [
+            ("v14.17.0", Some("Fermium"), "2021-05-11"),
+            ("v14.18.0", Some("Fermium"), "2021-09-28"),
+            ("v16.0.0", None, "2021-04-20"),
+            ("v16.13.0", Some("Gallium"), "2021-10-26"),
+            ("v16.14.0", Some("Gallium"), "2022-02-08"),
+            ("v17.0.0", None, "2021-10-19"),
+        ]
+        .iter()
+        .map(|(version, lts, date)| IndexedNodeVersion {
+            version: Version::parse(version).unwrap(),
+            lts: lts.map(String::from),
+            date: date.parse().unwrap(),
+            files: vec![],
+            npm: None,
+            v8: None,
+            uv: None,
+            zlib: None,
+            openssl: None,
+            modules: None,
+            security: false,
+        })
+        .collect()
+    }
+
+    fn filter(ls_remote: &LsRemote) -> Vec<String> {
+        ls_remote
+            .filter(index(), &FnmConfig::default())
+            .iter()
+            .map(|x| x.version.v_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_lts() {
+        let all_lts = LsRemote {
+            lts: Some(None),
+            ..LsRemote::default()
+        };
+        assert_eq!(
+            filter(&all_lts),
+            vec!["v14.17.0", "v14.18.0", "v16.13.0", "v16.14.0"]
+        );
+
+        let gallium = LsRemote {
+            lts: Some(Some("gallium".to_string())),
+            ..LsRemote::default()
+        };
+        assert_eq!(filter(&gallium), vec!["v16.13.0", "v16.14.0"]);
+    }
+
+    #[test]
+    fn test_filter_version_and_date() {
+        let ls_remote = LsRemote {
+            version: Some(UserVersion::OnlyMajor(16)),
+            since: "2021-10-01".parse().ok(),
+            ..LsRemote::default()
+        };
+        assert_eq!(filter(&ls_remote), vec!["v16.13.0", "v16.14.0"]);
+    }
+
+    #[test]
+    fn test_latest_per_major_descending() {
+        let ls_remote = LsRemote {
+            latest: true,
+            sort: SortingMethod::Descending,
+            ..LsRemote::default()
+        };
+        assert_eq!(filter(&ls_remote), vec!["v17.0.0", "v16.14.0", "v14.18.0"]);
+    }
 }
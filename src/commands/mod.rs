@@ -1,13 +1,18 @@
 pub mod alias;
+pub mod bundle;
+pub mod cache;
 pub mod command;
 pub mod completions;
 pub mod current;
 pub mod default;
 pub mod env;
 pub mod exec;
+pub mod import;
+pub mod info;
 pub mod install;
 pub mod ls_local;
 pub mod ls_remote;
+pub mod pin;
 pub mod unalias;
 pub mod uninstall;
 pub mod r#use;
@@ -0,0 +1,279 @@
+use super::command::Command;
+use super::r#use::Use;
+use crate::alias::StoredAlias;
+use crate::choose_version_for_user_input::{
+    choose_version_for_user_input, Error as ChooseVersionError,
+};
+use crate::config::FnmConfig;
+use crate::current_version::current_version;
+use crate::outln;
+use crate::remote_node_index;
+use crate::user_version::UserVersion;
+use crate::user_version_reader::UserVersionReader;
+use crate::version::Version;
+use crate::version_source::{PACKAGE_JSON, TOOL_VERSIONS};
+use colored::Colorize;
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use std::convert::TryInto;
+use std::path::Path;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+#[derive(StructOpt, Debug)]
+pub struct Pin {
+    /// The version to pin: an installed version, an alias, or a version from the remote index.
+    /// Defaults to the current version.
+    version: Option<UserVersion>,
+
+    /// The file to write the version to. Defaults to the first of `--version-files`.
+    /// `.tool-versions` keeps the versions of the other tools in it.
+    #[structopt(long)]
+    file: Option<String>,
+
+    /// How much of the version to write:
+    ///
+    /// * `full`: the exact version, like `18.12.1`
+    ///
+    /// * `minor`: the latest patch of the minor version, like `18.12`
+    ///
+    /// * `major`: the latest release of the major version, like `18`
+    #[structopt(
+        long,
+        default_value = "full",
+        possible_values = Precision::possible_values()
+    )]
+    precision: Precision,
+
+    /// Install the pinned version if it isn't installed yet
+    #[structopt(long)]
+    install_if_missing: bool,
+}
+
+impl Command for Pin {
+    type Error = Error;
+
+    fn apply(self, config: &FnmConfig) -> Result<(), Self::Error> {
+        let file_name = match self.file {
+            Some(file_name) => file_name,
+            None => config
+                .version_files()
+                .first()
+                .cloned()
+                .context(NoVersionFiles)?,
+        };
+        ensure!(file_name != PACKAGE_JSON, UnsupportedFile { file_name });
+
+        let version = resolve_version(self.version, config)?;
+        let pinned = self.precision.format(&version);
+        let current_dir = std::env::current_dir().context(IoError)?;
+        write_version_file(&current_dir.join(&file_name), &pinned).context(IoError)?;
+        outln!(
+            config,
+            Info,
+            "Pinned Node {} in {}",
+            pinned.cyan(),
+            file_name
+        );
+
+        let pinned = UserVersion::from_str(&pinned).context(InvalidPinnedVersion)?;
+        Use {
+            version: Some(UserVersionReader::Direct(pinned)),
+            install_if_missing: self.install_if_missing,
+            silent_if_unchanged: false,
+        }
+        .apply(config)
+        .context(UseError)
+    }
+}
+
+/// Resolves the requested version into an exact version: an installed one when possible,
+/// and the latest matching version in the remote index otherwise
+fn resolve_version(
+    requested_version: Option<UserVersion>,
+    config: &FnmConfig,
+) -> Result<semver::Version, Error> {
+    let requested_version = match requested_version {
+        Some(requested_version) => requested_version,
+        None => current_version(config)
+            .ok()
+            .flatten()
+            .map(UserVersion::Full)
+            .context(NoCurrentVersion)?,
+    };
+
+    let local_version = match choose_version_for_user_input(&requested_version, config) {
+        // Pinning the latest version needs the remote index, even if a version is installed
+        Ok(_) if matches!(requested_version, UserVersion::Latest) => None,
+        Ok(applicable) => applicable.and_then(|applicable| match applicable.version() {
+            Version::Alias(_) => TryInto::<StoredAlias>::try_into(applicable.path())
+                .ok()
+                .and_then(|alias| Version::parse(alias.s_ver()).ok()),
+            version => Some(version.clone()),
+        }),
+        // The LTS aliases only exist once a version of the LTS line is installed
+        Err(ChooseVersionError::CantFindVersion { .. }) => None,
+        Err(source) => return Err(Error::CantChooseVersion { source }),
+    };
+
+    let version = if let Some(version) = local_version {
+        version
+    } else {
+        let index = if config.offline() {
+            remote_node_index::list_offline(&config.node_index_path())
+        } else {
+            remote_node_index::list(&config.node_dist_mirrors(), &config.node_index_cache(false))
+        }
+        .context(CantListRemoteVersions)?;
+        super::info::find_in_index(&requested_version, index, config)
+            .with_context(|| VersionNotFound {
+                requested_version: requested_version.clone(),
+            })?
+            .version
+    };
+
+    match version {
+        Version::Semver(version) => Ok(version),
+        version => CantPinVersion { version }.fail(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    Full,
+    Minor,
+    Major,
+}
+
+impl Precision {
+    pub fn possible_values() -> &'static [&'static str] {
+        &["full", "minor", "major"]
+    }
+
+    fn format(self, version: &semver::Version) -> String {
+        match self {
+            Self::Full => version.to_string(),
+            Self::Minor => format!("{}.{}", version.major, version.minor),
+            Self::Major => version.major.to_string(),
+        }
+    }
+}
+
+impl FromStr for Precision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(Self::Full),
+            "minor" => Ok(Self::Minor),
+            "major" => Ok(Self::Major),
+            _ => Err(format!(
+                "Invalid precision: {}. Expected one of: {}",
+                s,
+                Self::possible_values().join(", ")
+            )),
+        }
+    }
+}
+
+/// Writes `version` to the version file at `path`. A `.tool-versions` file only has its
+/// `nodejs` line replaced, as it declares the versions of other tools as well.
+fn write_version_file(path: &Path, version: &str) -> std::io::Result<()> {
+    let is_tool_versions = path
+        .file_name()
+        .is_some_and(|file_name| file_name == TOOL_VERSIONS);
+    if !is_tool_versions {
+        return std::fs::write(path, format!("{}\n", version));
+    }
+
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let node_line = format!("nodejs {}", version);
+    let mut replaced = false;
+    let mut lines: Vec<String> = vec![];
+    for line in contents.lines() {
+        let is_node_line = matches!(line.split_whitespace().next(), Some("nodejs" | "node"));
+        if !is_node_line {
+            lines.push(line.to_string());
+        } else if !replaced {
+            lines.push(node_line.clone());
+            replaced = true;
+        }
+    }
+    if !replaced {
+        lines.push(node_line);
+    }
+    std::fs::write(path, lines.join("\n") + "\n")
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("{}", source))]
+    IoError { source: std::io::Error },
+    #[snafu(display("There are no version files to write to, please provide one with --file"))]
+    NoVersionFiles,
+    #[snafu(display(
+        "Can't pin the version in {}, please pin it with `volta pin` or in `engines.node`",
+        file_name
+    ))]
+    UnsupportedFile { file_name: String },
+    #[snafu(display(
+        "There is no current version to pin. Please provide a version to the command."
+    ))]
+    NoCurrentVersion,
+    #[snafu(display("{}", source))]
+    CantChooseVersion { source: ChooseVersionError },
+    #[snafu(display("Can't list the remote versions: {}", source))]
+    CantListRemoteVersions { source: remote_node_index::Error },
+    #[snafu(display(
+        "Can't find a Node version that matches {} locally or in remote",
+        requested_version
+    ))]
+    VersionNotFound { requested_version: UserVersion },
+    #[snafu(display("Can't pin {}, only Node versions can be pinned", version))]
+    CantPinVersion { version: Version },
+    #[snafu(display("Can't read the pinned version: {}", source))]
+    InvalidPinnedVersion { source: semver::Error },
+    #[snafu(display("{}", source))]
+    UseError { source: <Use as Command>::Error },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_precision() {
+        let version = semver::Version::parse("18.12.1").unwrap();
+        assert_eq!(Precision::Full.format(&version), "18.12.1");
+        assert_eq!(Precision::Minor.format(&version), "18.12");
+        assert_eq!(Precision::Major.format(&version), "18");
+    }
+
+    #[test]
+    fn test_write_version_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(".nvmrc");
+        std::fs::write(&path, "v16\n").unwrap();
+        write_version_file(&path, "18.12.1").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "18.12.1\n");
+    }
+
+    #[test]
+    fn test_write_tool_versions() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(TOOL_VERSIONS);
+        write_version_file(&path, "16").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "nodejs 16\n");
+
+        std::fs::write(&path, "ruby 3.2.0\nnodejs 16.20.0 14.21.3\npython 3.11.1").unwrap();
+        write_version_file(&path, "18.12.1").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "ruby 3.2.0\nnodejs 18.12.1\npython 3.11.1\n"
+        );
+    }
+}
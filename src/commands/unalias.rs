@@ -25,7 +25,7 @@ impl Command for Unalias {
             requested_alias: self.requested_alias,
         })?;
 
-        remove_symlink_dir(&requested_version.path()).context(CantDeleteSymlink)?;
+        remove_symlink_dir(requested_version.path()).context(CantDeleteSymlink)?;
 
         Ok(())
     }
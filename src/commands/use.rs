@@ -1,5 +1,6 @@
 use super::command::Command;
 use super::install::Install;
+use crate::choose_version_for_user_input::installed_lts_version;
 use crate::current_version::current_version;
 use crate::fs;
 use crate::installed_versions;
@@ -8,6 +9,7 @@ use crate::system_version;
 use crate::user_version::UserVersion;
 use crate::version::Version;
 use crate::version_file_strategy::VersionFileStrategy;
+use crate::version_source::VersionSource;
 use crate::{config::FnmConfig, user_version_reader::UserVersionReader};
 use colored::Colorize;
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
@@ -16,15 +18,15 @@ use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
 pub struct Use {
-    version: Option<UserVersionReader>,
+    pub version: Option<UserVersionReader>,
     /// Install the version if it isn't installed yet
     #[structopt(long)]
-    install_if_missing: bool,
+    pub install_if_missing: bool,
 
     /// Don't output a message identifying the version being used
     /// if it will not change due to execution of this command
     #[structopt(long)]
-    silent_if_unchanged: bool,
+    pub silent_if_unchanged: bool,
 }
 
 impl Command for Use {
@@ -36,18 +38,35 @@ impl Command for Use {
 
         let all_versions =
             installed_versions::list(config.installations_dir()).context(VersionListingError)?;
+        let current_dir = std::env::current_dir().unwrap();
+        let inferred_from_directory = self.version.is_none();
         let requested_version = self
             .version
-            .unwrap_or_else(|| {
-                let current_dir = std::env::current_dir().unwrap();
-                UserVersionReader::Path(current_dir)
-            })
-            .into_user_version(config)
-            .ok_or_else(|| match config.version_file_strategy() {
-                VersionFileStrategy::Local => InferVersionError::Local,
-                VersionFileStrategy::Recursive => InferVersionError::Recursive,
-            })
-            .context(CantInferVersion)?;
+            .unwrap_or_else(|| UserVersionReader::Path(current_dir.clone()))
+            .into_user_version(config);
+        let requested_version = match requested_version {
+            Some(requested_version) => requested_version,
+            // The use-on-cd hooks run for every package.json and .tool-versions,
+            // most of which don't declare a Node version
+            None if inferred_from_directory
+                && self.silent_if_unchanged
+                && config
+                    .version_sources()
+                    .iter()
+                    .filter(|source| **source != VersionSource::VersionFiles)
+                    .flat_map(|source| source.file_names(config))
+                    .any(|file_name| current_dir.join(file_name).exists()) =>
+            {
+                return Ok(());
+            }
+            None => {
+                return Err(match config.version_file_strategy() {
+                    VersionFileStrategy::Local => InferVersionError::Local,
+                    VersionFileStrategy::Recursive => InferVersionError::Recursive,
+                })
+                .context(CantInferVersion);
+            }
+        };
 
         let (message, version_path) = if let UserVersion::Full(Version::Bypassed) =
             requested_version
@@ -57,6 +76,11 @@ impl Command for Use {
                 system_version::display_name().cyan()
             );
             (message, system_version::path())
+        } else if let Some(version) =
+            installed_lts_version(&requested_version, &all_versions, config)
+        {
+            let message = format!("Using Node {}", version.to_string().cyan());
+            (message, version.installation_path(config))
         } else if let Some(alias_name) = requested_version.alias_name() {
             let alias_path = config.aliases_dir().join(&alias_name);
             let system_path = system_version::path();
@@ -143,9 +167,9 @@ fn install_new_version(
 ///
 /// This way, we can create a symlink if it is missing.
 fn replace_symlink(from: &std::path::Path, to: &std::path::Path) -> std::io::Result<()> {
-    let symlink_deletion_result = fs::remove_symlink_dir(&to);
-    match fs::symlink_dir(&from, &to) {
-        ok @ Ok(_) => ok,
+    let symlink_deletion_result = fs::remove_symlink_dir(to);
+    match fs::symlink_dir(from, to) {
+        ok @ Ok(()) => ok,
         err @ Err(_) => symlink_deletion_result.and(err),
     }
 }
@@ -203,8 +227,6 @@ fn warn_if_multishell_path_not_in_path_env_var(
 pub enum Error {
     #[snafu(display("Can't create the symlink: {}", source))]
     SymlinkingCreationIssue { source: std::io::Error },
-    #[snafu(display("Can't read the symlink: {}", source))]
-    SymlinkReadFailed { source: std::io::Error },
     #[snafu(display("{}", source))]
     InstallError { source: <Install as Command>::Error },
     #[snafu(display("Can't get locally installed versions: {}", source))]
@@ -231,3 +253,53 @@ pub enum InferVersionError {
     #[snafu(display("Could not find any version to use. Maybe you don't have a default version set?\nTry running `fnm default <VERSION>` to set one,\nor create a .node-version file inside your project to declare a Node.js version."))]
     Recursive,
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::importer::tests::create_fake_installation;
+    use crate::user_version::UserVersion;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_use_lts_lines_without_aliases() {
+        let base_dir = tempfile::tempdir().unwrap();
+        let config = FnmConfig::default()
+            .with_base_dir(Some(base_dir.path().to_path_buf()))
+            .with_multishell_path(base_dir.path().join("multishell"));
+        std::fs::create_dir_all(config.cache_dir()).unwrap();
+        std::fs::write(
+            config.node_index_path(),
+            serde_json::json!([
+                { "version": "v18.16.0", "lts": "Hydrogen", "date": "2023-04-12", "files": [] },
+                { "version": "v16.20.0", "lts": "Gallium", "date": "2023-03-28", "files": [] },
+                { "version": "v16.19.0", "lts": "Gallium", "date": "2022-12-13", "files": [] },
+                { "version": "v4.9.1", "lts": "Argon", "date": "2018-03-29", "files": [] },
+            ])
+            .to_string(),
+        )
+        .unwrap();
+        for version in ["v4.9.1", "v16.19.0", "v18.16.0"] {
+            let version = Version::parse(version).unwrap();
+            create_fake_installation(&version.installation_path(&config), &version.v_str());
+        }
+        let use_version = |version: &str| {
+            Use {
+                version: Some(UserVersionReader::Direct(
+                    UserVersion::from_str(version).unwrap(),
+                )),
+                install_if_missing: false,
+                silent_if_unchanged: false,
+            }
+            .apply(&config)
+            .expect("Can't use the version");
+            std::fs::read_link(config.multishell_path().unwrap()).unwrap()
+        };
+
+        let installation_path =
+            |version: &str| Version::parse(version).unwrap().installation_path(&config);
+        assert_eq!(use_version("lts/-1"), installation_path("v16.19.0"));
+        assert_eq!(use_version("lts/argon"), installation_path("v4.9.1"));
+    }
+}
@@ -1,10 +1,16 @@
 use crate::arch::Arch;
-use crate::archive;
-use crate::archive::{Error as ExtractError, Extract};
+use crate::archive::{ArchiveFormat, Error as ExtractError};
+use crate::archive_cache::{ArchiveCache, CacheEntry, Verification};
+use crate::checksum::{find_checksum, Sha256Reader};
 use crate::directory_portal::DirectoryPortal;
+use crate::http::Download;
+use crate::libc::Libc;
+use crate::signature::{self, Keyring};
+use crate::source_build::{self, BuildOptions};
 use crate::version::Version;
 use log::debug;
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use std::io::Read;
 use std::path::Path;
 use std::path::PathBuf;
 use url::Url;
@@ -14,6 +20,10 @@ pub enum Error {
     HttpError {
         source: crate::http::Error,
     },
+    #[snafu(display("Can't download the archive: {}", source))]
+    DownloadFailed {
+        source: crate::http::DownloadError,
+    },
     IoError {
         source: std::io::Error,
     },
@@ -24,99 +34,833 @@ pub enum Error {
     #[snafu(display("The downloaded archive is empty"))]
     TarIsEmpty,
     #[snafu(display(
-        "{} for {} not found upstream.\nYou can `fnm ls-remote` to see available versions or try a different `--arch`.",
+        "{} for {}{} not found upstream.\nYou can `fnm ls-remote` to see available versions or try a different `--arch`.",
         version,
-        arch
+        arch,
+        libc.build_suffix()
     ))]
     VersionNotFound {
         version: Version,
         arch: Arch,
+        libc: Libc,
+    },
+    #[snafu(display(
+        "The source code of {} is not found upstream.\nYou can `fnm ls-remote` to see available versions.",
+        version
+    ))]
+    SourceNotFound {
+        version: Version,
+    },
+    #[snafu(display("Can't build Node from source: {}", source))]
+    BuildFailed {
+        source: crate::source_build::Error,
+    },
+    #[snafu(display(
+        "{} for {}{} has no cached archive, so it can't be installed offline",
+        version,
+        arch,
+        libc.build_suffix()
+    ))]
+    NotAvailableOffline {
+        version: Version,
+        arch: Arch,
+        libc: Libc,
+    },
+    #[snafu(display(
+        "Can't install {}: archives must be one of: {}",
+        name,
+        supported_extensions()
+    ))]
+    UnsupportedArchive {
+        name: String,
+    },
+    #[snafu(display(
+        "Can't tell the Node version in {}. Name the archive like `node-v18.12.1-linux-x64.tar.xz`.",
+        name
+    ))]
+    CantDetectVersion {
+        name: String,
+    },
+    #[snafu(display("Can't find an archive at {}", url))]
+    ArchiveNotFound {
+        url: Url,
     },
     #[snafu(display("Version already installed at {:?}", path))]
     VersionAlreadyInstalled {
         path: PathBuf,
     },
+    #[snafu(display(
+        "Can't find the checksum of {} in {}.\nIf your mirror doesn't publish checksums, you can use `--skip-checksum-verification`.",
+        filename,
+        url
+    ))]
+    ChecksumNotFound {
+        filename: String,
+        url: Url,
+    },
+    #[snafu(display(
+        "Checksum mismatch for {}: expected {}, but the downloaded archive hashes to {}",
+        filename,
+        expected,
+        actual
+    ))]
+    ChecksumMismatch {
+        filename: String,
+        expected: String,
+        actual: String,
+    },
+    #[snafu(display(
+        "Can't find SHASUMS256.txt.sig or SHASUMS256.txt.asc for {} in the mirror",
+        version
+    ))]
+    SignatureNotFound {
+        version: Version,
+    },
+    #[snafu(display("Can't verify the signature of the checksums: {}", source))]
+    SignatureVerificationFailed {
+        source: signature::Error,
+    },
+}
+
+impl Error {
+    /// Whether the error means the mirror can't provide the archive, so another mirror
+    /// can be tried. Archives that fail verification are never retried from another mirror.
+    fn is_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Self::HttpError { .. }
+                | Self::DownloadFailed { .. }
+                | Self::VersionNotFound { .. }
+                | Self::SourceNotFound { .. }
+                | Self::ChecksumNotFound { .. }
+                | Self::SignatureNotFound { .. }
+        )
+    }
+}
+
+pub fn supported_extensions() -> String {
+    let extensions: Vec<_> = ArchiveFormat::all()
+        .iter()
+        .map(|format| format.extension())
+        .collect();
+    extensions.join(", ")
+}
+
+/// How downloaded archives are verified before being installed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveVerification {
+    /// Install archives without verifying them
+    Skip,
+    /// Verify archives against the `SHASUMS256.txt` of the release
+    Checksum,
+    /// Verify archives against the `SHASUMS256.txt` of the release,
+    /// after verifying its signature with the given keyring
+    Signature(Keyring),
+}
+
+impl ArchiveVerification {
+    /// The verification a cached archive needs to be installed without verifying it again
+    pub fn required_level(&self) -> Verification {
+        match self {
+            Self::Skip => Verification::Unverified,
+            Self::Checksum => Verification::Checksum,
+            Self::Signature(_) => Verification::Signature,
+        }
+    }
 }
 
 #[cfg(unix)]
-fn filename_for_version(version: &Version, arch: &Arch) -> String {
+pub fn filename_for_version(
+    version: &Version,
+    arch: &Arch,
+    libc: Libc,
+    format: ArchiveFormat,
+) -> String {
     format!(
-        "node-{node_ver}-{platform}-{arch}.tar.xz",
+        "node-{node_ver}-{platform}-{arch}{libc}.{extension}",
         node_ver = &version,
         platform = crate::system_info::platform_name(),
         arch = arch,
+        libc = libc.build_suffix(),
+        extension = format.extension(),
     )
 }
 
 #[cfg(windows)]
-fn filename_for_version(version: &Version, arch: &Arch) -> String {
+pub fn filename_for_version(
+    version: &Version,
+    arch: &Arch,
+    _libc: Libc,
+    format: ArchiveFormat,
+) -> String {
     format!(
-        "node-{node_ver}-win-{arch}.zip",
+        "node-{node_ver}-win-{arch}.{extension}",
         node_ver = &version,
         arch = arch,
+        extension = format.extension(),
     )
 }
 
-fn download_url(base_url: &Url, version: &Version, arch: &Arch) -> Url {
+fn download_url(base_url: &Url, version: &Version, filename: &str) -> Url {
     Url::parse(&format!(
         "{}/{}/{}",
         base_url.as_str().trim_end_matches('/'),
         version,
-        filename_for_version(version, arch)
+        filename
     ))
     .unwrap()
 }
 
-pub fn extract_archive_into<P: AsRef<Path>>(
-    path: P,
-    response: crate::http::Response,
-) -> Result<(), Error> {
-    #[cfg(unix)]
-    let extractor = archive::TarXz::new(response);
-    #[cfg(windows)]
-    let extractor = archive::Zip::new(response);
-    extractor.extract_into(path).context(CantExtractFile)?;
-    Ok(())
+/// The kind of archive to get from a release
+#[derive(Debug, Clone, Copy)]
+enum Artifact<'a> {
+    /// The prebuilt binaries for an arch and libc
+    Binary(&'a Arch, Libc),
+    /// The source code
+    Source,
 }
 
-/// Install a Node package
-pub fn install_node_dist<P: AsRef<Path>>(
+impl Artifact<'_> {
+    fn filename(self, version: &Version, format: ArchiveFormat) -> String {
+        match self {
+            Self::Binary(arch, libc) => filename_for_version(version, arch, libc, format),
+            Self::Source => format!("node-{}.{}", version, format.extension()),
+        }
+    }
+
+    fn not_found(self, version: &Version) -> Error {
+        match self {
+            Self::Binary(arch, libc) => Error::VersionNotFound {
+                version: version.clone(),
+                arch: arch.clone(),
+                libc,
+            },
+            Self::Source => Error::SourceNotFound {
+                version: version.clone(),
+            },
+        }
+    }
+}
+
+/// Fetches the body of `url`, or `None` if the mirror doesn't have it
+fn fetch_optional(url: &Url) -> Result<Option<Vec<u8>>, Error> {
+    debug!("Going to call for {}", url);
+    let response = crate::http::get(url.as_str()).context(HttpError)?;
+    if !response.status().is_success() {
+        debug!("{} responded with {}", url, response.status());
+        return Ok(None);
+    }
+    let body = response
+        .bytes()
+        .map_err(crate::http::Error::from)
+        .context(HttpError)?;
+    Ok(Some(body.to_vec()))
+}
+
+/// Fetches `SHASUMS256.txt` and verifies it was signed by a key in `keyring`.
+/// Releases publish both a detached signature (`.sig`) and a clearsigned copy (`.asc`),
+/// so try the former and fall back to the latter.
+fn fetch_signed_shasums(
+    base_url: &Url,
+    version: &Version,
+    keyring: &Keyring,
+) -> Result<String, Error> {
+    let sig_url = download_url(base_url, version, "SHASUMS256.txt.sig");
+    if let Some(signature) = fetch_optional(&sig_url)? {
+        let shasums_url = download_url(base_url, version, "SHASUMS256.txt");
+        let shasums = fetch_optional(&shasums_url)?.unwrap_or_default();
+        signature::verify_detached(&shasums, &signature, keyring)
+            .context(SignatureVerificationFailed)?;
+        return Ok(String::from_utf8_lossy(&shasums).into_owned());
+    }
+
+    let asc_url = download_url(base_url, version, "SHASUMS256.txt.asc");
+    let document = fetch_optional(&asc_url)?.with_context(|| SignatureNotFound {
+        version: version.clone(),
+    })?;
+    signature::verify_clearsigned(&document, keyring).context(SignatureVerificationFailed)
+}
+
+/// Fetches the `SHASUMS256.txt` file that is published alongside the archives of every release,
+/// verifying its signature when a keyring is given.
+/// Returns `None` when the mirror doesn't have a `SHASUMS256.txt` for this version.
+fn fetch_shasums(
+    base_url: &Url,
+    version: &Version,
+    keyring: Option<&Keyring>,
+) -> Result<Option<String>, Error> {
+    if let Some(keyring) = keyring {
+        return fetch_signed_shasums(base_url, version, keyring).map(Some);
+    }
+    let url = download_url(base_url, version, "SHASUMS256.txt");
+    let shasums = fetch_optional(&url)?.map(|body| String::from_utf8_lossy(&body).into_owned());
+    Ok(shasums)
+}
+
+/// The archive formats to try for a release, paired with their expected checksums.
+/// When the checksums are known, they tell which archives the release publishes.
+fn archive_candidates(
+    shasums: Option<&str>,
+    version: &Version,
+    artifact: Artifact<'_>,
+) -> Result<Vec<(ArchiveFormat, Option<String>)>, Error> {
+    let Some(shasums) = shasums else {
+        let candidates = ArchiveFormat::supported().iter();
+        return Ok(candidates.map(|format| (*format, None)).collect());
+    };
+
+    // The release is there, but it doesn't publish an archive we can install
+    let (format, checksum) = ArchiveFormat::supported()
+        .iter()
+        .find_map(|format| {
+            let filename = artifact.filename(version, *format);
+            let checksum = find_checksum(shasums, &filename)?;
+            Some((*format, checksum.to_lowercase()))
+        })
+        .ok_or_else(|| artifact.not_found(version))?;
+
+    Ok(vec![(format, Some(checksum))])
+}
+
+/// An archive to install, either from the cache or downloaded from the mirror
+struct ArchiveSource {
+    format: ArchiveFormat,
+    filename: String,
+    expected_checksum: Option<String>,
+    /// How `expected_checksum` was verified
+    verification: Verification,
+    path: PathBuf,
+    /// The cache entry of the archive, when it was cached
+    cached: Option<CacheEntry>,
+}
+
+impl ArchiveSource {
+    /// Removes an archive that failed to install, so it isn't used again
+    fn discard(&self, cache: Option<&ArchiveCache>) -> Result<(), Error> {
+        match (cache, &self.cached) {
+            (Some(cache), Some(cached)) => {
+                debug!("Removing corrupted archive {:?} from cache", cached.path());
+                cache.remove(cached.path()).context(IoError)
+            }
+            _ => std::fs::remove_file(&self.path).context(IoError),
+        }
+    }
+}
+
+/// Finds a cached archive of `version` that was verified as strongly as `verification`
+/// when it was stored, so it can be installed without fetching the checksums again
+fn find_verified_archive(
+    cache: &ArchiveCache,
+    version: &Version,
+    artifact: Artifact<'_>,
+    verification: Verification,
+) -> Option<ArchiveSource> {
+    ArchiveFormat::supported().iter().find_map(|format| {
+        let filename = artifact.filename(version, *format);
+        let cached = cache.find_verified(&filename, verification)?;
+        debug!("Using verified cached archive {:?}", cached.path());
+        Some(ArchiveSource {
+            format: *format,
+            filename,
+            expected_checksum: Some(cached.checksum().to_string()),
+            verification: cached.verification(),
+            path: cached.path().to_path_buf(),
+            cached: Some(cached),
+        })
+    })
+}
+
+/// Finds the first of `candidates` that is in the cache under its published checksum
+fn find_cached_archive(
+    cache: &ArchiveCache,
+    candidates: &[(ArchiveFormat, Option<String>)],
+    version: &Version,
+    artifact: Artifact<'_>,
+    verification: Verification,
+) -> Option<ArchiveSource> {
+    candidates.iter().find_map(|(format, expected_checksum)| {
+        let filename = artifact.filename(version, *format);
+        let cached = cache.find(&filename, Some(expected_checksum.as_deref()?))?;
+        debug!("Using cached archive {:?}", cached.path());
+        Some(ArchiveSource {
+            format: *format,
+            filename,
+            expected_checksum: expected_checksum.clone(),
+            verification,
+            path: cached.path().to_path_buf(),
+            cached: Some(cached),
+        })
+    })
+}
+
+/// Downloads the first of `candidates` that is available in the mirror into `downloads_dir`.
+/// A partial download left there by an interrupted install is resumed.
+fn download_archive(
+    candidates: Vec<(ArchiveFormat, Option<String>)>,
     version: &Version,
     node_dist_mirror: &Url,
+    artifact: Artifact<'_>,
+    downloads_dir: &Path,
+    verification: Verification,
+    show_progress: bool,
+) -> Result<ArchiveSource, Error> {
+    for (format, expected_checksum) in candidates {
+        let filename = artifact.filename(version, format);
+        let url = download_url(node_dist_mirror, version, &filename);
+        let path = downloads_dir.join(format!("{}.part", filename));
+        debug!("Going to call for {}", &url);
+
+        match crate::http::download(url.as_str(), &path, show_progress).context(DownloadFailed)? {
+            Download::NotFound => {
+                debug!("{} is not available in the mirror", &filename);
+            }
+            Download::Completed => {
+                return Ok(ArchiveSource {
+                    format,
+                    filename,
+                    expected_checksum,
+                    verification,
+                    path,
+                    cached: None,
+                });
+            }
+        }
+    }
+
+    Err(artifact.not_found(version))
+}
+
+/// Where the archive of an installation came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveOrigin {
+    Cache,
+    Mirror(Url),
+}
+
+impl std::fmt::Display for ArchiveOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cache => write!(f, "the archive cache"),
+            Self::Mirror(url) => write!(f, "{}", crate::http::without_credentials(url)),
+        }
+    }
+}
+
+/// Install a Node package.
+/// The mirrors are tried in order, falling back to the next one when the version
+/// isn't available in a mirror or the mirror can't be reached.
+/// When `show_progress` is set, a progress bar of the download is drawn on stderr.
+#[allow(clippy::too_many_arguments)]
+pub fn install_node_dist<P: AsRef<Path>>(
+    version: &Version,
+    node_dist_mirrors: &[Url],
     installations_dir: P,
     arch: &Arch,
-) -> Result<(), Error> {
-    let installation_dir = PathBuf::from(installations_dir.as_ref()).join(version.v_str());
+    libc: Libc,
+    verification: &ArchiveVerification,
+    cache: Option<&ArchiveCache>,
+    show_progress: bool,
+) -> Result<ArchiveOrigin, Error> {
+    let portal = prepare_installation(version, installations_dir.as_ref())?;
+    let downloads_dir = installations_dir.as_ref().join(".downloads");
+    let (origin, archive) = fetch_from_mirrors(node_dist_mirrors, version, |mirror| {
+        fetch_archive(
+            version,
+            mirror,
+            Artifact::Binary(arch, libc),
+            verification,
+            cache,
+            &downloads_dir,
+            show_progress,
+        )
+    })?;
+
+    debug!("Installing {} from {}", &archive.filename, origin);
+    extract_verified(&archive, cache, &portal)?;
+    finish_installation(portal)?;
+    Ok(origin)
+}
+
+/// Fetches an archive with `fetch` from the first of `mirrors` that provides it
+fn fetch_from_mirrors(
+    mirrors: &[Url],
+    version: &Version,
+    fetch: impl Fn(&Url) -> Result<ArchiveSource, Error>,
+) -> Result<(ArchiveOrigin, ArchiveSource), Error> {
+    let (last_mirror, fallback_mirrors) = mirrors
+        .split_last()
+        .expect("There's always at least one mirror");
+    let mut fetched = None;
+    for mirror in fallback_mirrors {
+        match fetch(mirror) {
+            Ok(archive) => {
+                fetched = Some((mirror, archive));
+                break;
+            }
+            Err(err) if err.is_unavailable() => {
+                debug!(
+                    "Can't get {} from {}, trying the next mirror: {}",
+                    version, mirror, err
+                );
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    let (mirror, archive) = match fetched {
+        Some(fetched) => fetched,
+        None => (last_mirror, fetch(last_mirror)?),
+    };
 
+    let origin = if archive.cached.is_some() {
+        ArchiveOrigin::Cache
+    } else {
+        ArchiveOrigin::Mirror(mirror.clone())
+    };
+    Ok((origin, archive))
+}
+
+/// Build a Node package from the source code published in the mirrors, for platforms
+/// without prebuilt binaries. The mirrors are tried like in [`install_node_dist`].
+pub fn install_node_source<P: AsRef<Path>>(
+    version: &Version,
+    node_dist_mirrors: &[Url],
+    installations_dir: P,
+    verification: &ArchiveVerification,
+    cache: Option<&ArchiveCache>,
+    build_options: &BuildOptions,
+    show_progress: bool,
+) -> Result<ArchiveOrigin, Error> {
+    let portal = prepare_installation(version, installations_dir.as_ref())?;
+    let downloads_dir = installations_dir.as_ref().join(".downloads");
+    let (origin, archive) = fetch_from_mirrors(node_dist_mirrors, version, |mirror| {
+        fetch_archive(
+            version,
+            mirror,
+            Artifact::Source,
+            verification,
+            cache,
+            &downloads_dir,
+            show_progress,
+        )
+    })?;
+
+    debug!("Building {} from {}", &archive.filename, origin);
+    let build_dir = tempfile::TempDir::new_in(&downloads_dir).context(IoError)?;
+    extract_verified(&archive, cache, build_dir.path())?;
+    let source_dir = std::fs::read_dir(&build_dir)
+        .context(IoError)?
+        .next()
+        .context(TarIsEmpty)?
+        .context(IoError)?
+        .path();
+    source_build::build(&source_dir, &portal.join("installation"), build_options)
+        .context(BuildFailed)?;
+    source_build::BuildMetadata::new(version, build_options)
+        .write(&portal)
+        .context(BuildFailed)?;
+
+    portal.teleport().context(IoError)?;
+    Ok(origin)
+}
+
+/// Finds the archive of `version` in the cache, or downloads it from `node_dist_mirror`
+fn fetch_archive(
+    version: &Version,
+    node_dist_mirror: &Url,
+    artifact: Artifact<'_>,
+    verification: &ArchiveVerification,
+    cache: Option<&ArchiveCache>,
+    downloads_dir: &Path,
+    show_progress: bool,
+) -> Result<ArchiveSource, Error> {
+    let required_verification = verification.required_level();
+    if let Some(archive) = cache
+        .and_then(|cache| find_verified_archive(cache, version, artifact, required_verification))
+    {
+        return Ok(archive);
+    }
+
+    let shasums = match verification {
+        ArchiveVerification::Skip => {
+            debug!("Skipping checksum verification for {}", version);
+            None
+        }
+        ArchiveVerification::Checksum => fetch_shasums(node_dist_mirror, version, None)?,
+        ArchiveVerification::Signature(keyring) => {
+            fetch_shasums(node_dist_mirror, version, Some(keyring))?
+        }
+    };
     ensure!(
-        !installation_dir.exists(),
-        VersionAlreadyInstalled {
-            path: installation_dir
+        shasums.is_some() || *verification == ArchiveVerification::Skip,
+        ChecksumNotFound {
+            url: download_url(node_dist_mirror, version, "SHASUMS256.txt"),
+            filename: artifact.filename(version, ArchiveFormat::supported()[0]),
         }
     );
+    let candidates = archive_candidates(shasums.as_deref(), version, artifact)?;
 
-    std::fs::create_dir_all(installations_dir.as_ref()).context(IoError)?;
+    if let Some(archive) = cache.and_then(|cache| {
+        find_cached_archive(cache, &candidates, version, artifact, required_verification)
+    }) {
+        return Ok(archive);
+    }
 
-    let temp_installations_dir = installations_dir.as_ref().join(".downloads");
-    std::fs::create_dir_all(&temp_installations_dir).context(IoError)?;
+    download_archive(
+        candidates,
+        version,
+        node_dist_mirror,
+        artifact,
+        downloads_dir,
+        required_verification,
+        show_progress,
+    )
+}
+
+/// Extracts `archive` into `path`, verifying its checksum, and stores it in the cache
+fn extract_verified(
+    archive: &ArchiveSource,
+    cache: Option<&ArchiveCache>,
+    path: &Path,
+) -> Result<(), Error> {
+    let file = std::fs::File::open(&archive.path).context(IoError)?;
+    let actual_checksum = match extract_and_hash(path, archive.format, file) {
+        Ok(checksum) => checksum,
+        Err(err) => {
+            archive.discard(cache)?;
+            return Err(err);
+        }
+    };
+    if let Some(expected) = &archive.expected_checksum {
+        if actual_checksum != *expected {
+            archive.discard(cache)?;
+            return Err(Error::ChecksumMismatch {
+                filename: archive.filename.clone(),
+                expected: expected.clone(),
+                actual: actual_checksum,
+            });
+        }
+        debug!("Checksum verified for {}", &archive.filename);
+    }
 
-    let portal = DirectoryPortal::new_in(&temp_installations_dir, installation_dir);
+    let stored = cache.is_some_and(|cache| {
+        let stored = if archive.cached.is_some() {
+            Ok(())
+        } else {
+            cache
+                .insert(&archive.filename, &actual_checksum, &archive.path)
+                .map(|_| ())
+        };
+        match stored.and_then(|()| cache.set_verification(&actual_checksum, archive.verification)) {
+            Ok(()) => true,
+            Err(err) => {
+                debug!("Can't store {} in the cache: {}", &archive.filename, err);
+                false
+            }
+        }
+    });
+    if !stored && archive.cached.is_none() && archive.path.exists() {
+        std::fs::remove_file(&archive.path).context(IoError)?;
+    }
 
-    let url = download_url(node_dist_mirror, version, arch);
-    debug!("Going to call for {}", &url);
-    let response = crate::http::get(url.as_str()).context(HttpError)?;
+    Ok(())
+}
 
-    if response.status() == 404 {
-        return Err(Error::VersionNotFound {
+/// Install a Node package from the archive cache, without accessing the network.
+/// Only archives that were cached with the given verification are used,
+/// and they are verified against the checksum they were stored with.
+pub fn install_node_dist_offline<P: AsRef<Path>>(
+    version: &Version,
+    installations_dir: P,
+    arch: &Arch,
+    libc: Libc,
+    verification: &ArchiveVerification,
+    cache: &ArchiveCache,
+) -> Result<(), Error> {
+    let portal = prepare_installation(version, installations_dir.as_ref())?;
+
+    let (format, cached_archive) = ArchiveFormat::supported()
+        .iter()
+        .find_map(|format| {
+            let filename = filename_for_version(version, arch, libc, *format);
+            let cached = cache.find_verified(&filename, verification.required_level())?;
+            Some((*format, cached))
+        })
+        .with_context(|| NotAvailableOffline {
             version: version.clone(),
             arch: arch.clone(),
+            libc,
+        })?;
+    debug!("Using cached archive {:?}", cached_archive.path());
+
+    let archive = std::fs::File::open(cached_archive.path()).context(IoError)?;
+    let actual_checksum = extract_and_hash(&portal, format, archive)?;
+    if actual_checksum != cached_archive.checksum() {
+        debug!(
+            "Removing corrupted archive {:?} from cache",
+            cached_archive.path()
+        );
+        cache.remove(cached_archive.path()).context(IoError)?;
+        return Err(Error::ChecksumMismatch {
+            filename: cached_archive.filename().to_string(),
+            expected: cached_archive.checksum().to_string(),
+            actual: actual_checksum,
         });
     }
 
-    debug!("Extracting response...");
-    extract_archive_into(&portal, response)?;
+    finish_installation(portal)
+}
+
+/// Install a Node package from an archive that isn't published in a mirror, like a custom build.
+/// The version is read from the archive name, like `node-v18.12.1-linux-x64.tar.xz`,
+/// or from the extracted `node --version` when the name has no version.
+pub fn install_node_archive<P: AsRef<Path>>(
+    archive_path: &Path,
+    installations_dir: P,
+) -> Result<Version, Error> {
+    let name = archive_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let format = ArchiveFormat::from_filename(&name).context(UnsupportedArchive { name: &name })?;
+
+    // The version may only be known after extracting, so extract next to the installations
+    // and move the result into a portal once the version is known
+    let downloads_dir = installations_dir.as_ref().join(".downloads");
+    std::fs::create_dir_all(&downloads_dir).context(IoError)?;
+    let extracted = tempfile::TempDir::new_in(&downloads_dir).context(IoError)?;
+    let file = std::fs::File::open(archive_path).context(IoError)?;
+    debug!("Extracting {:?}...", archive_path);
+    format
+        .extract_into(file, extracted.path())
+        .context(CantExtractFile)?;
+    let extracted_directory = std::fs::read_dir(&extracted)
+        .context(IoError)?
+        .next()
+        .context(TarIsEmpty)?
+        .context(IoError)?
+        .path();
+
+    let version = version_from_filename(&name)
+        .or_else(|| installed_version(&extracted_directory))
+        .context(CantDetectVersion { name: &name })?;
+    debug!("{} contains Node {}", &name, version);
+
+    let portal = prepare_installation(&version, installations_dir.as_ref())?;
+    std::fs::rename(extracted_directory, portal.join("installation")).context(IoError)?;
+    portal.teleport().context(IoError)?;
+    Ok(version)
+}
+
+/// Downloads the archive at `url` and installs it, see [`install_node_archive`]
+pub fn install_node_archive_from_url<P: AsRef<Path>>(
+    url: &Url,
+    installations_dir: P,
+    show_progress: bool,
+) -> Result<Version, Error> {
+    let name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default()
+        .to_string();
+    ensure!(
+        ArchiveFormat::from_filename(&name).is_some(),
+        UnsupportedArchive { name }
+    );
+
+    let downloads_dir = installations_dir.as_ref().join(".downloads");
+    std::fs::create_dir_all(&downloads_dir).context(IoError)?;
+    let path = downloads_dir.join(format!("{}.part", name));
+    debug!("Going to call for {}", url);
+    match crate::http::download(url.as_str(), &path, show_progress).context(DownloadFailed)? {
+        Download::NotFound => return ArchiveNotFound { url: url.clone() }.fail(),
+        Download::Completed => {}
+    }
+
+    // The archive is renamed so its format and version can be read from the name
+    let archive_path = downloads_dir.join(&name);
+    std::fs::rename(&path, &archive_path).context(IoError)?;
+    let installation = install_node_archive(&archive_path, installations_dir);
+    std::fs::remove_file(&archive_path).context(IoError)?;
+    installation
+}
+
+/// The version of the extracted Node installation at `installation_dir`, from `node --version`
+pub fn installed_version(installation_dir: &Path) -> Option<Version> {
+    let node = if cfg!(windows) {
+        installation_dir.join("node.exe")
+    } else {
+        installation_dir.join("bin").join("node")
+    };
+    let output = match std::process::Command::new(&node).arg("--version").output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            debug!("{:?} exited with {}", node, output.status);
+            return None;
+        }
+        Err(err) => {
+            debug!("Can't run {:?}: {}", node, err);
+            return None;
+        }
+    };
+    semver_version(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+/// The version in the name of a release archive, like `node-v18.12.1-linux-x64.tar.xz`
+pub fn version_from_filename(filename: &str) -> Option<Version> {
+    let version = filename.strip_prefix("node-")?.split('-').next()?;
+    semver_version(version)
+}
+
+fn semver_version(version: &str) -> Option<Version> {
+    match Version::parse(version) {
+        Ok(version @ Version::Semver(_)) => Some(version),
+        _ => None,
+    }
+}
+
+fn prepare_installation(
+    version: &Version,
+    installations_dir: &Path,
+) -> Result<DirectoryPortal<PathBuf>, Error> {
+    let installation_dir = installations_dir.join(version.v_str());
+
+    ensure!(
+        !installation_dir.exists(),
+        VersionAlreadyInstalled {
+            path: installation_dir
+        }
+    );
+
+    std::fs::create_dir_all(installations_dir).context(IoError)?;
+
+    let temp_installations_dir = installations_dir.join(".downloads");
+    std::fs::create_dir_all(&temp_installations_dir).context(IoError)?;
+
+    Ok(DirectoryPortal::new_in(
+        &temp_installations_dir,
+        installation_dir,
+    ))
+}
+
+/// Extracts `archive` into `path` and returns its SHA-256 checksum
+fn extract_and_hash(
+    path: &Path,
+    format: ArchiveFormat,
+    archive: impl Read,
+) -> Result<String, Error> {
+    debug!("Extracting archive...");
+    let mut reader = Sha256Reader::new(archive);
+    format
+        .extract_into(&mut reader, path)
+        .context(CantExtractFile)?;
+    // The extractors may stop before the end of the stream (e.g. on tar padding),
+    // so drain it to make sure the checksum covers the whole archive
+    std::io::copy(&mut reader, &mut std::io::sink()).context(IoError)?;
     debug!("Extraction completed");
+    Ok(reader.hex_digest())
+}
 
+fn finish_installation(portal: DirectoryPortal<PathBuf>) -> Result<(), Error> {
     let installed_directory = std::fs::read_dir(&portal)
         .context(IoError)?
         .next()
@@ -176,12 +920,544 @@ mod tests {
         assert_eq!(result.trim(), "6.9.0");
     }
 
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_verifies_checksum() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+
+        install_node_dist(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &ArchiveVerification::Checksum,
+            None,
+            false,
+        )
+        .expect("Can't install from the test mirror");
+
+        assert!(installations_dir
+            .path()
+            .join("v14.0.0/installation/bin/node")
+            .exists());
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_refuses_archive_with_wrong_checksum() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        let filename = filename_for_version(
+            &Version::parse("14.0.0").unwrap(),
+            &Arch::X64,
+            Libc::Glibc,
+            ArchiveFormat::TarXz,
+        );
+        let wrong_checksum = "0".repeat(64);
+        mirror.write_file(
+            "v14.0.0/SHASUMS256.txt",
+            format!("{}  {}\n", wrong_checksum, filename),
+        );
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+
+        let result = install_node_dist(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &ArchiveVerification::Checksum,
+            None,
+            false,
+        );
+
+        match result {
+            Err(Error::ChecksumMismatch { expected, .. }) => assert_eq!(expected, wrong_checksum),
+            other => panic!("Expected a checksum mismatch, got {:?}", other),
+        }
+        assert!(!installations_dir.path().join("v14.0.0").exists());
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_missing_checksums() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        std::fs::remove_file(mirror.path().join("v14.0.0/SHASUMS256.txt")).unwrap();
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+
+        let result = install_node_dist(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &ArchiveVerification::Checksum,
+            None,
+            false,
+        );
+        assert!(matches!(result, Err(Error::ChecksumNotFound { .. })));
+
+        install_node_dist(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &ArchiveVerification::Skip,
+            None,
+            false,
+        )
+        .expect("Can't install without checksum verification");
+        assert!(installations_dir.path().join("v14.0.0").exists());
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_verifies_signature() {
+        use crate::signature::tests::TestKey;
+
+        let key = TestKey::generate();
+        let keyring_dir = tempdir().unwrap();
+        let keyring = keyring_dir.path().join("keyring.gpg");
+        key.export_keyring(&keyring);
+
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        mirror.add_release("v16.0.0", "x64");
+        let shasums = std::fs::read(mirror.path().join("v14.0.0/SHASUMS256.txt")).unwrap();
+        mirror.write_file("v14.0.0/SHASUMS256.txt.sig", key.sign_detached(&shasums));
+        let shasums = std::fs::read(mirror.path().join("v16.0.0/SHASUMS256.txt")).unwrap();
+        mirror.write_file("v16.0.0/SHASUMS256.txt.asc", key.clearsign(&shasums));
+
+        let installations_dir = tempdir().unwrap();
+        let verification = ArchiveVerification::Signature(Keyring::Path(keyring));
+        for version in ["14.0.0", "16.0.0"] {
+            let version = Version::parse(version).unwrap();
+            install_node_dist(
+                &version,
+                &[mirror.url().clone()],
+                installations_dir.path(),
+                &Arch::X64,
+                Libc::Glibc,
+                &verification,
+                None,
+                false,
+            )
+            .expect("Can't install a signed release");
+        }
+
+        let other_key = TestKey::generate();
+        mirror.add_release("v18.0.0", "x64");
+        let shasums = std::fs::read(mirror.path().join("v18.0.0/SHASUMS256.txt")).unwrap();
+        mirror.write_file(
+            "v18.0.0/SHASUMS256.txt.sig",
+            other_key.sign_detached(&shasums),
+        );
+        let result = install_node_dist(
+            &Version::parse("18.0.0").unwrap(),
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &verification,
+            None,
+            false,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::SignatureVerificationFailed { .. })
+        ));
+        assert!(!installations_dir.path().join("v18.0.0").exists());
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_reinstalls_from_cache() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        let cache_dir = tempdir().unwrap();
+        let cache = ArchiveCache::new(cache_dir.path());
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+        let install = |verification: &ArchiveVerification| {
+            install_node_dist(
+                &version,
+                &[mirror.url().clone()],
+                installations_dir.path(),
+                &Arch::X64,
+                Libc::Glibc,
+                verification,
+                Some(&cache),
+                false,
+            )
+        };
+
+        install(&ArchiveVerification::Checksum).expect("Can't install from the test mirror");
+        assert_eq!(cache.entries().unwrap().len(), 1);
+
+        std::fs::remove_dir_all(mirror.path().join("v14.0.0")).unwrap();
+        std::fs::remove_dir_all(installations_dir.path().join("v14.0.0")).unwrap();
+        install(&ArchiveVerification::Checksum).expect("Can't install from the cache");
+        assert!(installations_dir
+            .path()
+            .join("v14.0.0/installation/bin/node")
+            .exists());
+
+        std::fs::remove_dir_all(installations_dir.path().join("v14.0.0")).unwrap();
+        install(&ArchiveVerification::Skip).expect("Can't install from the cache by file name");
+    }
+
+    #[test]
+    fn test_verifies_archives_cached_without_verification() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        std::fs::remove_file(mirror.path().join("v14.0.0/SHASUMS256.txt")).unwrap();
+        let cache_dir = tempdir().unwrap();
+        let cache = ArchiveCache::new(cache_dir.path());
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+        let install = |verification: &ArchiveVerification| {
+            install_node_dist(
+                &version,
+                &[mirror.url().clone()],
+                installations_dir.path(),
+                &Arch::X64,
+                Libc::Glibc,
+                verification,
+                Some(&cache),
+                false,
+            )
+        };
+
+        install(&ArchiveVerification::Skip).expect("Can't install without checksums");
+        assert_eq!(cache.entries().unwrap().len(), 1);
+
+        std::fs::remove_dir_all(installations_dir.path().join("v14.0.0")).unwrap();
+        let result = install(&ArchiveVerification::Checksum);
+        assert!(matches!(result, Err(Error::ChecksumNotFound { .. })));
+        assert!(!installations_dir.path().join("v14.0.0").exists());
+    }
+
+    #[test]
+    fn test_offline_install_requires_verified_archive() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        std::fs::remove_file(mirror.path().join("v14.0.0/SHASUMS256.txt")).unwrap();
+        let cache_dir = tempdir().unwrap();
+        let cache = ArchiveCache::new(cache_dir.path());
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+        install_node_dist(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &ArchiveVerification::Skip,
+            Some(&cache),
+            false,
+        )
+        .expect("Can't install without checksums");
+        std::fs::remove_dir_all(installations_dir.path().join("v14.0.0")).unwrap();
+        let install_offline = |verification: &ArchiveVerification| {
+            install_node_dist_offline(
+                &version,
+                installations_dir.path(),
+                &Arch::X64,
+                Libc::Glibc,
+                verification,
+                &cache,
+            )
+        };
+
+        let result = install_offline(&ArchiveVerification::Checksum);
+        assert!(matches!(result, Err(Error::NotAvailableOffline { .. })));
+        assert!(!installations_dir.path().join("v14.0.0").exists());
+
+        install_offline(&ArchiveVerification::Skip).expect("Can't install the unverified archive");
+        assert!(installations_dir
+            .path()
+            .join("v14.0.0/installation/bin/node")
+            .exists());
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_resumes_partial_download() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        let version = Version::parse("14.0.0").unwrap();
+        let filename =
+            filename_for_version(&version, &Arch::X64, Libc::Glibc, ArchiveFormat::TarXz);
+        let archive = std::fs::read(mirror.path().join("v14.0.0").join(&filename)).unwrap();
+        let installations_dir = tempdir().unwrap();
+        let downloads_dir = installations_dir.path().join(".downloads");
+        std::fs::create_dir_all(&downloads_dir).unwrap();
+        let partial = downloads_dir.join(format!("{}.part", filename));
+        std::fs::write(&partial, &archive[..archive.len() / 2]).unwrap();
+
+        install_node_dist(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &ArchiveVerification::Checksum,
+            None,
+            false,
+        )
+        .expect("Can't resume the download");
+
+        assert!(mirror
+            .requests()
+            .contains(&format!("206 /dist/v14.0.0/{}", filename)));
+        assert!(!partial.exists());
+        assert!(installations_dir
+            .path()
+            .join("v14.0.0/installation/bin/node")
+            .exists());
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_falls_back_to_tar_gz() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release_as("v0.12.0", "x64", ArchiveFormat::TarGz);
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("0.12.0").unwrap();
+        let node_path = installations_dir
+            .path()
+            .join("v0.12.0/installation/bin/node");
+
+        install_node_dist(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &ArchiveVerification::Checksum,
+            None,
+            false,
+        )
+        .expect("Can't install a tar.gz release");
+        assert!(node_path.exists());
+
+        // Without checksums, the formats are tried in order of preference
+        std::fs::remove_dir_all(installations_dir.path().join("v0.12.0")).unwrap();
+        std::fs::remove_file(mirror.path().join("v0.12.0/SHASUMS256.txt")).unwrap();
+        install_node_dist(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &Arch::X64,
+            Libc::Glibc,
+            &ArchiveVerification::Skip,
+            None,
+            false,
+        )
+        .expect("Can't install a tar.gz release without checksums");
+        assert!(node_path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_installs_musl_build() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64-musl");
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+        let install = |libc: Libc| {
+            install_node_dist(
+                &version,
+                &[mirror.url().clone()],
+                installations_dir.path(),
+                &Arch::X64,
+                libc,
+                &ArchiveVerification::Checksum,
+                None,
+                false,
+            )
+        };
+
+        let result = install(Libc::Glibc);
+        assert!(matches!(result, Err(Error::VersionNotFound { .. })));
+        install(Libc::Musl).expect("Can't install a musl build");
+        assert!(installations_dir
+            .path()
+            .join("v14.0.0/installation/bin/node")
+            .exists());
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_falls_back_to_next_mirror() {
+        let empty_mirror = crate::test_mirror::TestMirror::start();
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+        let install = |mirrors: &[Url]| {
+            install_node_dist(
+                &version,
+                mirrors,
+                installations_dir.path(),
+                &Arch::X64,
+                Libc::Glibc,
+                &ArchiveVerification::Checksum,
+                None,
+                false,
+            )
+        };
+
+        let origin = install(&[empty_mirror.url().clone(), mirror.url().clone()])
+            .expect("Can't install from the second mirror");
+        assert_eq!(origin, ArchiveOrigin::Mirror(mirror.url().clone()));
+        assert_eq!(
+            empty_mirror.requests(),
+            vec!["404 /dist/v14.0.0/SHASUMS256.txt"]
+        );
+
+        // An archive that fails verification isn't downloaded again from another mirror
+        std::fs::remove_dir_all(installations_dir.path().join("v14.0.0")).unwrap();
+        let tampered_mirror = crate::test_mirror::TestMirror::start();
+        tampered_mirror.add_release("v14.0.0", "x64");
+        let filename =
+            filename_for_version(&version, &Arch::X64, Libc::Glibc, ArchiveFormat::TarXz);
+        tampered_mirror.write_file(
+            "v14.0.0/SHASUMS256.txt",
+            format!("{}  {}\n", "0".repeat(64), filename),
+        );
+        let result = install(&[tampered_mirror.url().clone(), mirror.url().clone()]);
+        assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_installs_archive_from_file_and_url() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_release("v14.0.0", "x64");
+        mirror.add_release("v16.0.0", "x64");
+        let installations_dir = tempdir().unwrap();
+        let archives_dir = tempdir().unwrap();
+        let filename = |version: &str| {
+            let version = Version::parse(version).unwrap();
+            filename_for_version(&version, &Arch::X64, Libc::Glibc, ArchiveFormat::TarXz)
+        };
+
+        // A custom build without a version in its name is detected by running it
+        let custom_build = archives_dir.path().join("patched-node.tar.xz");
+        std::fs::copy(
+            mirror.path().join("v14.0.0").join(filename("14.0.0")),
+            &custom_build,
+        )
+        .unwrap();
+        let version = install_node_archive(&custom_build, installations_dir.path())
+            .expect("Can't install a local archive");
+        assert_eq!(version, Version::parse("14.0.0").unwrap());
+        assert!(installations_dir
+            .path()
+            .join("v14.0.0/installation/bin/node")
+            .exists());
+
+        let url = mirror
+            .url()
+            .join(&format!("dist/v16.0.0/{}", filename("16.0.0")))
+            .unwrap();
+        let version = install_node_archive_from_url(&url, installations_dir.path(), false)
+            .expect("Can't install an archive from a URL");
+        assert_eq!(version, Version::parse("16.0.0").unwrap());
+        assert!(installations_dir.path().join("v16.0.0").exists());
+        assert!(!installations_dir
+            .path()
+            .join(".downloads")
+            .join(filename("16.0.0"))
+            .exists());
+
+        // The version in the name is used without running the binary
+        let renamed_build = archives_dir.path().join(filename("15.0.0"));
+        std::fs::copy(&custom_build, &renamed_build).unwrap();
+        let version = install_node_archive(&renamed_build, installations_dir.path())
+            .expect("Can't install a renamed archive");
+        assert_eq!(version, Version::parse("15.0.0").unwrap());
+
+        let result = install_node_archive(&custom_build, installations_dir.path());
+        assert!(matches!(result, Err(Error::VersionAlreadyInstalled { .. })));
+        let result = install_node_archive(
+            &archives_dir.path().join("node.rar"),
+            installations_dir.path(),
+        );
+        assert!(matches!(result, Err(Error::UnsupportedArchive { .. })));
+    }
+
+    #[cfg(unix)]
+    #[test_log::test]
+    fn test_builds_from_source() {
+        let mirror = crate::test_mirror::TestMirror::start();
+        mirror.add_source("v14.0.0");
+        let installations_dir = tempdir().unwrap();
+        let version = Version::parse("14.0.0").unwrap();
+        let build_options = BuildOptions {
+            jobs: 2,
+            configure_flags: vec!["--fully-static".to_string()],
+            show_output: false,
+        };
+
+        let origin = install_node_source(
+            &version,
+            &[mirror.url().clone()],
+            installations_dir.path(),
+            &ArchiveVerification::Checksum,
+            None,
+            &build_options,
+            false,
+        )
+        .expect("Can't build from source");
+        assert_eq!(origin, ArchiveOrigin::Mirror(mirror.url().clone()));
+
+        let version_dir = installations_dir.path().join("v14.0.0");
+        let stdout = duct::cmd(version_dir.join("installation/bin/node"), vec!["--version"])
+            .read()
+            .expect("Can't run the built binary");
+        assert_eq!(stdout.trim(), "v14.0.0");
+
+        let metadata = source_build::BuildMetadata::read(&version_dir).expect("No build metadata");
+        assert_eq!(metadata.version, "v14.0.0");
+        assert_eq!(metadata.configure_flags, vec!["--fully-static"]);
+        assert_eq!(metadata.jobs, 2);
+    }
+
+    #[test]
+    fn test_version_from_filename() {
+        assert_eq!(
+            version_from_filename("node-v18.12.1-linux-x64.tar.xz"),
+            Some(Version::parse("18.12.1").unwrap())
+        );
+        assert_eq!(version_from_filename("node-latest-linux-x64.tar.xz"), None);
+        assert_eq!(version_from_filename("patched-node.tar.xz"), None);
+    }
+
     fn install_in(path: &Path) -> PathBuf {
         let version = Version::parse("12.0.0").unwrap();
         let arch = Arch::X64;
         let node_dist_mirror = Url::parse("https://nodejs.org/dist/").unwrap();
-        install_node_dist(&version, &node_dist_mirror, &path, &arch)
-            .expect("Can't install Node 12");
+        install_node_dist(
+            &version,
+            &[node_dist_mirror],
+            path,
+            &arch,
+            Libc::Glibc,
+            &ArchiveVerification::Checksum,
+            None,
+            false,
+        )
+        .expect("Can't install Node 12");
 
         let mut location_path = path.join(version.v_str()).join("installation");
 
@@ -27,3 +27,36 @@ pub fn remove_symlink_dir<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
 pub fn shallow_read_symlink<P: AsRef<Path>>(path: P) -> std::io::Result<std::path::PathBuf> {
     std::fs::read_link(path)
 }
+
+/// Recreates the directory tree of `from` in `to`, hard linking the files when `hard_link`
+/// is set, and copying them when it isn't or when linking fails (e.g. across file systems).
+/// Symlinks are recreated as they are, so relative links like `bin/npm` keep working.
+pub fn link_or_copy_dir(from: &Path, to: &Path, hard_link: bool) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            link_or_copy_dir(&source, &target, hard_link)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&source, &target)?;
+        } else if !hard_link || std::fs::hard_link(&source, &target).is_err() {
+            std::fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)
+}
+
+/// Creating symlinks needs special privileges on Windows, so the linked file is copied instead
+#[cfg(windows)]
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::copy(from, to)?;
+    Ok(())
+}
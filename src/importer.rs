@@ -0,0 +1,276 @@
+//! Imports the Node installations of other version managers, so switching to fnm
+//! doesn't mean downloading every version again.
+//!
+//! All of the supported version managers keep one regular Node installation per version,
+//! so importing one is a matter of recreating its files in `installations_dir`.
+
+use crate::directory_portal::DirectoryPortal;
+use crate::downloader::installed_version;
+use crate::version::Version;
+use log::debug;
+use std::path::{Path, PathBuf};
+
+/// How many aliases pointing to aliases are followed when resolving an nvm alias
+const MAX_ALIAS_DEPTH: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Nvm,
+    N,
+    Volta,
+    Asdf,
+    /// A directory of installations, or a single installation
+    Path,
+}
+
+impl Source {
+    pub fn possible_values() -> &'static [&'static str] {
+        &["nvm", "n", "volta", "asdf", "path"]
+    }
+
+    /// The directory the version manager keeps its data in, which is configurable
+    /// with the same environment variable the version manager itself reads
+    pub fn default_dir(self) -> Option<PathBuf> {
+        let from_env = |name: &str| std::env::var_os(name).map(PathBuf::from);
+        let in_home = |name: &str| dirs::home_dir().map(|home| home.join(name));
+        match self {
+            Self::Nvm => from_env("NVM_DIR").or_else(|| in_home(".nvm")),
+            Self::N => from_env("N_PREFIX").or_else(|| Some(PathBuf::from("/usr/local"))),
+            Self::Volta => from_env("VOLTA_HOME").or_else(|| in_home(".volta")),
+            Self::Asdf => from_env("ASDF_DATA_DIR").or_else(|| in_home(".asdf")),
+            Self::Path => None,
+        }
+    }
+
+    /// The directory containing one installation per version, within `dir`
+    fn versions_dir(self, dir: &Path) -> PathBuf {
+        match self {
+            Self::Nvm => dir.join("versions").join("node"),
+            Self::N => dir.join("n").join("versions").join("node"),
+            Self::Volta => dir.join("tools").join("image").join("node"),
+            Self::Asdf => dir.join("installs").join("nodejs"),
+            Self::Path => dir.to_path_buf(),
+        }
+    }
+}
+
+impl std::str::FromStr for Source {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Source, Self::Err> {
+        match s {
+            "nvm" => Ok(Self::Nvm),
+            "n" => Ok(Self::N),
+            "volta" => Ok(Self::Volta),
+            "asdf" => Ok(Self::Asdf),
+            "path" => Ok(Self::Path),
+            unknown => Err(format!("Unknown version manager: {}", unknown)),
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Nvm => "nvm",
+            Self::N => "n",
+            Self::Volta => "volta",
+            Self::Asdf => "asdf",
+            Self::Path => "path",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Installation {
+    pub path: PathBuf,
+    /// The version reported by `node --version`, or `None` when it can't run
+    pub version: Option<Version>,
+}
+
+/// The installations of `source` in its directory `dir`, sorted by path.
+///
+/// The directory names aren't trusted, as each version manager names them differently:
+/// the version comes from running the `node` binary of the installation.
+pub fn discover(source: Source, dir: &Path) -> std::io::Result<Vec<Installation>> {
+    let versions_dir = source.versions_dir(dir);
+
+    if source == Source::Path {
+        if let Some(version) = installed_version(&versions_dir) {
+            return Ok(vec![Installation {
+                path: versions_dir,
+                version: Some(version),
+            }]);
+        }
+    }
+
+    let mut paths = vec![];
+    for entry in std::fs::read_dir(&versions_dir)? {
+        let entry = entry?;
+        let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !is_hidden && entry.path().is_dir() {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let version = installed_version(&path);
+            debug!("Found {:?} in {:?}", version, path);
+            Installation { path, version }
+        })
+        .collect())
+}
+
+/// Imports the installation at `path` as `version` into `installations_dir`.
+/// The files are hard linked when `hard_link` is set and the directories are on the same
+/// file system, and copied otherwise.
+pub fn import(
+    path: &Path,
+    version: &Version,
+    installations_dir: &Path,
+    hard_link: bool,
+) -> std::io::Result<()> {
+    let temp_installations_dir = installations_dir.join(".downloads");
+    std::fs::create_dir_all(&temp_installations_dir)?;
+    let portal = DirectoryPortal::new_in(
+        &temp_installations_dir,
+        installations_dir.join(version.v_str()),
+    );
+    crate::fs::link_or_copy_dir(path, &portal.join("installation"), hard_link)?;
+    portal.teleport()?;
+    Ok(())
+}
+
+/// The aliases of nvm in its directory `dir`, with what they point to.
+///
+/// nvm stores every alias as a file containing a version, or the name of another alias,
+/// like `default` containing `lts/*`. Aliases to aliases are followed, so the targets are
+/// either version requirements like `v18` or `18.12.1`, or `node` for the latest version.
+pub fn nvm_aliases(dir: &Path) -> std::io::Result<Vec<(String, String)>> {
+    let aliases_dir = dir.join("alias");
+    let mut aliases = vec![];
+    for entry in std::fs::read_dir(&aliases_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            // The `lts` directory has the aliases nvm creates for LTS releases
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let mut target = std::fs::read_to_string(entry.path())?.trim().to_string();
+        for _ in 0..MAX_ALIAS_DEPTH {
+            let aliased = aliases_dir.join(&target);
+            if !aliased.is_file() {
+                break;
+            }
+            target = std::fs::read_to_string(aliased)?.trim().to_string();
+        }
+        aliases.push((name, target));
+    }
+    aliases.sort();
+    Ok(aliases)
+}
+
+#[cfg(all(test, unix))]
+pub(crate) mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Creates an installation with a `node` binary that prints `version`
+    pub fn create_fake_installation(path: &Path, version: &str) {
+        let bin = path.join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        let node = bin.join("node");
+        std::fs::write(&node, format!("#!/bin/sh\necho {}\n", version)).unwrap();
+        std::fs::set_permissions(&node, std::os::unix::fs::PermissionsExt::from_mode(0o755))
+            .unwrap();
+        std::os::unix::fs::symlink("node", bin.join("nodejs")).unwrap();
+    }
+
+    #[test]
+    fn test_discover() {
+        let layouts = [
+            (Source::Nvm, "versions/node/v18.12.1"),
+            (Source::N, "n/versions/node/18.12.1"),
+            (Source::Volta, "tools/image/node/18.12.1"),
+            (Source::Asdf, "installs/nodejs/18.12.1"),
+            (Source::Path, "node-18"),
+        ];
+        for (source, installation) in layouts {
+            let dir = tempfile::tempdir().unwrap();
+            create_fake_installation(&dir.path().join(installation), "v18.12.1");
+            let broken = dir.path().join(installation).with_file_name("1.0.0");
+            std::fs::create_dir_all(&broken).unwrap();
+
+            let installations = discover(source, dir.path()).unwrap();
+            assert_eq!(
+                installations,
+                vec![
+                    Installation {
+                        path: broken,
+                        version: None,
+                    },
+                    Installation {
+                        path: dir.path().join(installation),
+                        version: Some(Version::parse("v18.12.1").unwrap()),
+                    },
+                ],
+                "{} layout",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_discover_single_installation() {
+        let dir = tempfile::tempdir().unwrap();
+        create_fake_installation(dir.path(), "v16.0.0");
+        let installations = discover(Source::Path, dir.path()).unwrap();
+        assert_eq!(
+            installations,
+            vec![Installation {
+                path: dir.path().to_path_buf(),
+                version: Some(Version::parse("v16.0.0").unwrap()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_import_keeps_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let installation = dir.path().join("v18.12.1");
+        create_fake_installation(&installation, "v18.12.1");
+        let installations_dir = dir.path().join("node-versions");
+        let version = Version::parse("v18.12.1").unwrap();
+
+        import(&installation, &version, &installations_dir, true).unwrap();
+
+        let imported = installations_dir.join("v18.12.1").join("installation");
+        assert_eq!(installed_version(&imported), Some(version));
+        assert_eq!(
+            std::fs::read_link(imported.join("bin").join("nodejs")).unwrap(),
+            PathBuf::from("node")
+        );
+    }
+
+    #[test]
+    fn test_nvm_aliases() {
+        let dir = tempfile::tempdir().unwrap();
+        let aliases_dir = dir.path().join("alias");
+        std::fs::create_dir_all(aliases_dir.join("lts")).unwrap();
+        std::fs::write(aliases_dir.join("default"), "lts/*\n").unwrap();
+        std::fs::write(aliases_dir.join("work"), "16\n").unwrap();
+        std::fs::write(aliases_dir.join("lts").join("*"), "lts/hydrogen\n").unwrap();
+        std::fs::write(aliases_dir.join("lts").join("hydrogen"), "v18.12.1\n").unwrap();
+
+        assert_eq!(
+            nvm_aliases(dir.path()).unwrap(),
+            vec![
+                ("default".to_string(), "v18.12.1".to_string()),
+                ("work".to_string(), "16".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! The C library Node binaries are linked against.
+//!
+//! Official Linux builds are linked against glibc, so they don't run on distributions
+//! like Alpine that use musl. Builds for musl are published by the unofficial builds project.
+
+use log::debug;
+use std::sync::OnceLock;
+
+/// The unofficial builds mirror, which publishes the musl builds
+pub const UNOFFICIAL_BUILDS_MIRROR: &str = "https://unofficial-builds.nodejs.org/download/release";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Libc {
+    Glibc,
+    Musl,
+}
+
+impl Libc {
+    /// Detects the libc of the system, once per process.
+    /// Only Linux has builds for more than one libc, so other platforms are always `Glibc`.
+    pub fn detect() -> Self {
+        static DETECTED: OnceLock<Libc> = OnceLock::new();
+        *DETECTED.get_or_init(|| {
+            let libc = if cfg!(target_os = "linux") && is_musl() {
+                Self::Musl
+            } else {
+                Self::Glibc
+            };
+            debug!("Detected {} as the libc of the system", libc);
+            libc
+        })
+    }
+
+    /// The suffix of builds for this libc in archive names and in the `files` of the index,
+    /// e.g. `linux-x64-musl`
+    pub fn build_suffix(self) -> &'static str {
+        match self {
+            Self::Glibc => "",
+            Self::Musl => "-musl",
+        }
+    }
+
+    pub fn possible_values() -> &'static [&'static str] {
+        &["glibc", "musl"]
+    }
+}
+
+/// musl systems have a dynamic loader named like `/lib/ld-musl-x86_64.so.1`.
+/// Otherwise, `ldd` tells which libc it belongs to.
+fn is_musl() -> bool {
+    let has_musl_loader = std::fs::read_dir("/lib").is_ok_and(|entries| {
+        entries.filter_map(Result::ok).any(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with("ld-musl-"))
+        })
+    });
+    if has_musl_loader {
+        return true;
+    }
+
+    // musl's `ldd` prints its version to stderr, and exits with an error
+    match std::process::Command::new("ldd").arg("--version").output() {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            stdout.contains("musl") || stderr.contains("musl")
+        }
+        Err(err) => {
+            debug!("Can't run ldd to detect the libc: {}", err);
+            false
+        }
+    }
+}
+
+impl std::str::FromStr for Libc {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Libc, Self::Err> {
+        match s {
+            "glibc" => Ok(Libc::Glibc),
+            "musl" => Ok(Libc::Musl),
+            unknown => Err(format!("Unknown libc: {}", unknown)),
+        }
+    }
+}
+
+impl std::fmt::Display for Libc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Libc::Glibc => write!(f, "glibc"),
+            Libc::Musl => write!(f, "musl"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() {
+        for value in Libc::possible_values() {
+            let libc: Libc = value.parse().unwrap();
+            assert_eq!(&libc.to_string(), value);
+        }
+        assert!("uclibc".parse::<Libc>().is_err());
+    }
+}
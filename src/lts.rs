@@ -1,4 +1,5 @@
 use crate::remote_node_index::IndexedNodeVersion;
+use crate::version::Version;
 use std::fmt::Display;
 
 #[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Clone)]
@@ -7,12 +8,16 @@ pub enum LtsType {
     Latest,
     /// lts-erbium, lts/erbium
     CodeName(String),
+    /// lts--1, lts/-1: the LTS line before the latest one
+    Offset(usize),
 }
 
 impl From<&str> for LtsType {
     fn from(s: &str) -> Self {
         if s == "*" || s == "latest" {
             Self::Latest
+        } else if let Some(offset) = s.strip_prefix('-').and_then(|x| x.parse().ok()) {
+            Self::Offset(offset)
         } else {
             Self::CodeName(s.to_string())
         }
@@ -24,6 +29,7 @@ impl Display for LtsType {
         match self {
             Self::Latest => write!(f, "latest"),
             Self::CodeName(s) => write!(f, "{}", s),
+            Self::Offset(offset) => write!(f, "-{}", offset),
         }
     }
 }
@@ -34,14 +40,100 @@ impl LtsType {
         versions: &'vec [IndexedNodeVersion],
     ) -> Option<&'vec IndexedNodeVersion> {
         match self {
-            Self::Latest => versions.iter().filter(|x| x.lts.is_some()).last(),
-            Self::CodeName(s) => versions
-                .iter()
-                .filter(|x| match &x.lts {
-                    None => false,
-                    Some(x) => s.to_lowercase() == x.to_lowercase(),
-                })
-                .last(),
+            Self::Latest => versions.iter().rfind(|x| x.lts.is_some()),
+            Self::CodeName(s) => versions.iter().rfind(|x| match &x.lts {
+                None => false,
+                Some(x) => s.to_lowercase() == x.to_lowercase(),
+            }),
+            Self::Offset(offset) => {
+                // The LTS lines, from the one with the newest release
+                let mut code_names: Vec<&str> = vec![];
+                for lts in versions.iter().rev().filter_map(|x| x.lts.as_deref()) {
+                    if !code_names.iter().any(|x| x.eq_ignore_ascii_case(lts)) {
+                        code_names.push(lts);
+                    }
+                }
+                Self::CodeName(code_names.get(*offset)?.to_string()).pick_latest(versions)
+            }
         }
     }
+
+    /// The newest of the `installed` versions in the LTS line this refers to,
+    /// with the LTS lines read from the `index` of Node.js versions
+    pub fn pick_installed<'vec>(
+        &self,
+        index: &[IndexedNodeVersion],
+        installed: &'vec [Version],
+    ) -> Option<&'vec Version> {
+        let code_name = self.pick_latest(index)?.lts.as_deref()?;
+        installed
+            .iter()
+            .filter(|version| {
+                index.iter().any(|x| {
+                    &x.version == *version
+                        && x.lts
+                            .as_deref()
+                            .is_some_and(|lts| lts.eq_ignore_ascii_case(code_name))
+                })
+            })
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn indexed(version: &str, lts: Option<&str>) -> IndexedNodeVersion {
+        serde_json::from_value(serde_json::json!({
+            "version": version,
+            "lts": lts.map_or(serde_json::Value::Bool(false), serde_json::Value::from),
+            "date": "2022-01-01",
+            "files": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pick_offset() {
+        let versions = [
+            indexed("v14.21.3", Some("Fermium")),
+            indexed("v16.19.0", Some("Gallium")),
+            indexed("v16.20.0", Some("Gallium")),
+            indexed("v18.16.0", Some("Hydrogen")),
+            indexed("v19.9.0", None),
+        ];
+        let pick = |lts: &str| {
+            LtsType::from(lts)
+                .pick_latest(&versions)
+                .map(|x| x.version.v_str())
+        };
+
+        assert_eq!(pick("-0"), Some("v18.16.0".to_string()));
+        assert_eq!(pick("-1"), Some("v16.20.0".to_string()));
+        assert_eq!(pick("-2"), Some("v14.21.3".to_string()));
+        assert_eq!(pick("-3"), None);
+        assert_eq!(LtsType::from("-1").to_string(), "-1");
+    }
+
+    #[test]
+    fn test_pick_installed() {
+        let index = [
+            indexed("v4.9.1", Some("Argon")),
+            indexed("v16.19.0", Some("Gallium")),
+            indexed("v16.20.0", Some("Gallium")),
+            indexed("v18.16.0", Some("Hydrogen")),
+        ];
+        let installed = ["v4.9.1", "v16.19.0", "v17.0.0"].map(|x| Version::parse(x).unwrap());
+        let pick = |lts: &str| {
+            LtsType::from(lts)
+                .pick_installed(&index, &installed)
+                .map(Version::v_str)
+        };
+
+        assert_eq!(pick("-1"), Some("v16.19.0".to_string()));
+        assert_eq!(pick("argon"), Some("v4.9.1".to_string()));
+        assert_eq!(pick("-0"), None);
+    }
 }
@@ -3,12 +3,17 @@
     clippy::enum_variant_names,
     clippy::large_enum_variant,
     clippy::module_name_repetitions,
-    clippy::similar_names
+    clippy::needless_raw_string_hashes,
+    clippy::similar_names,
+    clippy::uninlined_format_args
 )]
 
 mod alias;
 mod arch;
 mod archive;
+mod archive_cache;
+mod bundle;
+mod checksum;
 mod choose_version_for_user_input;
 mod cli;
 mod commands;
@@ -18,18 +23,28 @@ mod directory_portal;
 mod downloader;
 mod fs;
 mod http;
+mod importer;
 mod installed_versions;
+mod libc;
 mod lts;
+mod netrc;
 mod path_ext;
+mod progress;
 mod remote_node_index;
 mod shell;
+mod signature;
+mod source_build;
 mod system_info;
 mod system_version;
+#[cfg(test)]
+mod test_mirror;
 mod user_version;
 mod user_version_reader;
 mod version;
 mod version_file_strategy;
 mod version_files;
+mod version_range;
+mod version_source;
 
 #[macro_use]
 mod log_level;
@@ -38,5 +53,6 @@ mod default_version;
 fn main() {
     env_logger::init();
     let value = crate::cli::parse();
+    crate::http::configure(value.config.http_options());
     value.subcmd.call(value.config);
 }
@@ -0,0 +1,127 @@
+//! A parser of `.netrc` files, which store the credentials of hosts for tools like `curl`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Login {
+    pub login: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Netrc {
+    machines: Vec<(String, Login)>,
+    default: Option<Login>,
+}
+
+impl Netrc {
+    pub fn parse(contents: &str) -> Self {
+        let mut netrc = Self::default();
+        let mut machine: Option<Option<String>> = None;
+        let mut login = String::new();
+        let mut password = String::new();
+        let mut in_macro = false;
+
+        let mut finish_machine = |machine: Option<Option<String>>, login: &str, password: &str| {
+            let credentials = Login {
+                login: login.to_string(),
+                password: password.to_string(),
+            };
+            match machine {
+                Some(Some(host)) => netrc.machines.push((host, credentials)),
+                Some(None) => netrc.default = Some(credentials),
+                None => {}
+            }
+        };
+
+        for line in contents.lines() {
+            // Macro definitions end with an empty line
+            if in_macro {
+                in_macro = !line.trim().is_empty();
+                continue;
+            }
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            while let Some(token) = tokens.next() {
+                match token {
+                    "machine" | "default" => {
+                        finish_machine(machine.take(), &login, &password);
+                        login.clear();
+                        password.clear();
+                        machine = Some(if token == "machine" {
+                            tokens.next().map(String::from)
+                        } else {
+                            None
+                        });
+                    }
+                    "login" => login = tokens.next().unwrap_or_default().to_string(),
+                    "password" => password = tokens.next().unwrap_or_default().to_string(),
+                    "account" => {
+                        tokens.next();
+                    }
+                    "macdef" => {
+                        in_macro = true;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        finish_machine(machine, &login, &password);
+
+        netrc
+    }
+
+    /// The credentials of `host`, or the default credentials when it isn't listed
+    pub fn find(&self, host: &str) -> Option<&Login> {
+        self.machines
+            .iter()
+            .find(|(machine, _)| machine.eq_ignore_ascii_case(host))
+            .map(|(_, login)| login)
+            .or(self.default.as_ref())
+    }
+}
+
+/// The `.netrc` file in the home directory, named `_netrc` on Windows
+pub fn default_path() -> Option<std::path::PathBuf> {
+    let filename = if cfg!(windows) { "_netrc" } else { ".netrc" };
+    dirs::home_dir().map(|home| home.join(filename))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse() {
+        let netrc = Netrc::parse(indoc::indoc! {"
+            # Artifactory
+            machine artifactory.example.com
+              login ci
+              password s3cret
+
+            macdef init
+            machine ignored.example.com login nobody password nothing
+
+            machine github.com login octocat password token account ignored
+            default login anonymous password guest
+        "});
+
+        let login = |login: &str, password: &str| Login {
+            login: login.to_string(),
+            password: password.to_string(),
+        };
+        assert_eq!(
+            netrc.find("artifactory.example.com"),
+            Some(&login("ci", "s3cret"))
+        );
+        assert_eq!(netrc.find("GitHub.com"), Some(&login("octocat", "token")));
+        assert_eq!(
+            netrc.find("ignored.example.com"),
+            Some(&login("anonymous", "guest"))
+        );
+        assert_eq!(Netrc::parse("").find("github.com"), None);
+    }
+}
@@ -0,0 +1,203 @@
+//! A progress bar for downloads, drawn on stderr while the download is read.
+
+use crate::log_level::LogLevel;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+const BAR_WIDTH: usize = 30;
+
+/// Progress is only drawn when stderr is a terminal and fnm isn't asked to be quiet,
+/// so it never ends up in logs or in the output of scripts
+pub fn is_enabled(log_level: &LogLevel) -> bool {
+    log_level.is_writable(&LogLevel::Info) && atty::is(atty::Stream::Stderr)
+}
+
+/// A reader that draws a progress bar of the bytes read through it
+pub struct ProgressReader<R: Read> {
+    inner: R,
+    total: Option<u64>,
+    resumed_from: u64,
+    read: u64,
+    started: Instant,
+    last_drawn: Option<Instant>,
+}
+
+impl<R: Read> ProgressReader<R> {
+    /// `total` is the expected size, usually the `Content-Length` of the response.
+    /// `resumed_from` is the size that was already downloaded before, when resuming a download.
+    pub fn new(inner: R, total: Option<u64>, resumed_from: u64) -> Self {
+        Self {
+            inner,
+            total,
+            resumed_from,
+            read: 0,
+            started: Instant::now(),
+            last_drawn: None,
+        }
+    }
+
+    fn draw(&mut self) {
+        let line = render(
+            self.resumed_from + self.read,
+            self.total,
+            speed(self.read, self.started.elapsed()),
+        );
+        let mut stderr = std::io::stderr();
+        let _ = write!(stderr, "\r{}\x1b[K", line);
+        let _ = stderr.flush();
+        self.last_drawn = Some(Instant::now());
+    }
+
+    fn clear(&mut self) {
+        if self.last_drawn.take().is_some() {
+            let mut stderr = std::io::stderr();
+            let _ = write!(stderr, "\r\x1b[K");
+            let _ = stderr.flush();
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read_bytes = self.inner.read(buf)?;
+        self.read += read_bytes as u64;
+
+        if read_bytes == 0 {
+            self.clear();
+        } else if self
+            .last_drawn
+            .is_none_or(|last_drawn| last_drawn.elapsed() >= REDRAW_INTERVAL)
+        {
+            self.draw();
+        }
+
+        Ok(read_bytes)
+    }
+}
+
+impl<R: Read> Drop for ProgressReader<R> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn render(read: u64, total: Option<u64>, speed: f64) -> String {
+    let total = match total {
+        Some(total) if total > 0 => total,
+        _ => return format!("{}  {}/s", format_bytes(read as f64), format_bytes(speed)),
+    };
+
+    let ratio = (read as f64 / total as f64).min(1.0);
+    let filled = (ratio * BAR_WIDTH as f64) as usize;
+    let bar = if filled >= BAR_WIDTH {
+        "=".repeat(BAR_WIDTH)
+    } else {
+        format!(
+            "{}>{}",
+            "=".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled - 1)
+        )
+    };
+
+    let eta = if speed > 0.0 {
+        let remaining = total.saturating_sub(read) as f64 / speed;
+        format!(
+            "ETA {}",
+            format_duration(Duration::from_secs_f64(remaining))
+        )
+    } else {
+        "ETA --".to_string()
+    };
+
+    format!(
+        "[{}] {} / {}  {}/s  {}",
+        bar,
+        format_bytes(read as f64),
+        format_bytes(total as f64),
+        format_bytes(speed),
+        eta
+    )
+}
+
+/// The speed of a download in bytes per second
+#[allow(clippy::cast_precision_loss)]
+fn speed(read: u64, elapsed: Duration) -> f64 {
+    let elapsed_secs = elapsed.as_secs_f64();
+    if elapsed_secs > 0.0 {
+        read as f64 / elapsed_secs
+    } else {
+        0.0
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", value as u64, UNITS[unit])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_render_with_total() {
+        let mib = 1024 * 1024;
+        assert_eq!(
+            render(
+                10 * mib,
+                Some(40 * mib),
+                speed(10 * mib, Duration::from_secs(5))
+            ),
+            "[=======>                      ] 10.0 MiB / 40.0 MiB  2.0 MiB/s  ETA 15s"
+        );
+        assert_eq!(
+            render(
+                40 * mib,
+                Some(40 * mib),
+                speed(40 * mib, Duration::from_secs(100))
+            ),
+            "[==============================] 40.0 MiB / 40.0 MiB  409.6 KiB/s  ETA 0s"
+        );
+    }
+
+    #[test]
+    fn test_render_without_total() {
+        assert_eq!(
+            render(512, None, speed(512, Duration::from_secs(2))),
+            "512 B  256 B/s"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(75)), "1m15s");
+        assert_eq!(format_duration(Duration::from_secs(9)), "9s");
+    }
+}
@@ -1,5 +1,11 @@
+use crate::arch::Arch;
+use crate::libc::Libc;
 use crate::version::Version;
-use serde::Deserialize;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use url::Url;
 
 mod lts_status {
@@ -57,38 +63,318 @@ mod lts_status {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct IndexedNodeVersion {
     pub version: Version,
     #[serde(with = "lts_status")]
     pub lts: Option<String>,
     pub date: chrono::NaiveDate,
     pub files: Vec<String>,
+    #[serde(default)]
+    pub npm: Option<String>,
+    #[serde(default)]
+    pub v8: Option<String>,
+    #[serde(default)]
+    pub uv: Option<String>,
+    #[serde(default)]
+    pub zlib: Option<String>,
+    #[serde(default)]
+    pub openssl: Option<String>,
+    /// The `NODE_MODULE_VERSION` of the release's native addon ABI
+    #[serde(default)]
+    pub modules: Option<String>,
+    /// Whether the release contains security fixes
+    #[serde(default)]
+    pub security: bool,
 }
 
-/// Prints
+impl IndexedNodeVersion {
+    /// Whether the release has a build for `arch` and `libc` on the current platform
+    pub fn has_build_for(&self, arch: &Arch, libc: Libc) -> bool {
+        let build_name = build_name(arch, libc);
+        self.files.contains(&build_name)
+    }
+
+    /// The arches the release has builds for on the current platform and `libc`
+    pub fn available_arches(&self, libc: Libc) -> Vec<&str> {
+        let (platform, suffix) = BUILD_PLATFORM;
+        self.files
+            .iter()
+            .filter_map(|file| {
+                let arch = file
+                    .strip_prefix(platform)?
+                    .strip_prefix('-')?
+                    .strip_suffix(suffix)?
+                    .strip_suffix(libc.build_suffix())?;
+                (!arch.contains('-')).then_some(arch)
+            })
+            .collect()
+    }
+}
+
+/// How builds of the current platform are named in the `files` of the index,
+/// as a platform name and a suffix for the archive type fnm downloads
+#[cfg(target_os = "linux")]
+const BUILD_PLATFORM: (&str, &str) = ("linux", "");
+#[cfg(target_os = "macos")]
+const BUILD_PLATFORM: (&str, &str) = ("osx", "-tar");
+#[cfg(windows)]
+const BUILD_PLATFORM: (&str, &str) = ("win", "-zip");
+
+/// The name of the build for `arch` and `libc` on the current platform in the `files` of the index,
+/// e.g. `linux-x64`, `linux-x64-musl` or `osx-arm64-tar`
+pub fn build_name(arch: &Arch, libc: Libc) -> String {
+    let (platform, suffix) = BUILD_PLATFORM;
+    format!("{}-{}{}{}", platform, arch, libc.build_suffix(), suffix)
+}
+
+/// Where the fetched `index.json` is stored, and when to fetch it again
+#[derive(Debug, Clone)]
+pub struct IndexCache {
+    pub path: PathBuf,
+    /// How long the stored index is used without asking the mirror whether it changed
+    pub ttl: Duration,
+    /// Revalidate the stored index with the mirror, even if it is within the TTL
+    pub refresh: bool,
+}
+
+impl IndexCache {
+    fn metadata_path(&self) -> PathBuf {
+        self.path.with_extension("meta.json")
+    }
+
+    /// Reads the stored index, if it was fetched from `url`
+    fn read(&self, url: &str) -> Option<(IndexMetadata, Vec<u8>)> {
+        let metadata = std::fs::read(self.metadata_path()).ok()?;
+        let metadata: IndexMetadata = serde_json::from_slice(&metadata).ok()?;
+        if metadata.url != url {
+            return None;
+        }
+        let body = std::fs::read(&self.path).ok()?;
+        Some((metadata, body))
+    }
+
+    fn write(&self, metadata: &IndexMetadata, body: Option<&[u8]>) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if let Some(body) = body {
+            std::fs::write(&self.path, body)?;
+        }
+        let metadata = serde_json::to_vec(metadata)?;
+        std::fs::write(self.metadata_path(), metadata)
+    }
+
+    /// Removes the stored index, so it is fetched again on the next use
+    pub fn clear(&self) -> std::io::Result<()> {
+        for path in [self.path.clone(), self.metadata_path()] {
+            match std::fs::remove_file(path) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The response headers of the stored index, used for conditional requests
+#[derive(Serialize, Deserialize, Debug)]
+struct IndexMetadata {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// Seconds since the epoch
+    fetched_at: u64,
+}
+
+impl IndexMetadata {
+    fn age(&self) -> Duration {
+        let fetched_at = SystemTime::UNIX_EPOCH + Duration::from_secs(self.fetched_at);
+        SystemTime::now()
+            .duration_since(fetched_at)
+            .unwrap_or_default()
+    }
+}
+
+fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Lists the versions in the `index.json` of the first of `mirrors` that provides it.
 ///
-/// ```rust
-/// use crate::remote_node_index::list;
-/// ```
-pub fn list(base_url: &Url) -> Result<Vec<IndexedNodeVersion>, crate::http::Error> {
-    let index_json_url = format!("{}/index.json", base_url);
-    let resp = crate::http::get(&index_json_url)?;
-    let mut value: Vec<IndexedNodeVersion> = resp.json()?;
+/// The index is stored in `cache`, and reused while it is within the TTL. After that,
+/// it is revalidated using `If-None-Match`/`If-Modified-Since` so an unchanged index
+/// isn't downloaded again. The stored index is also used by [`list_offline`].
+pub fn list(mirrors: &[Url], cache: &IndexCache) -> Result<Vec<IndexedNodeVersion>, Error> {
+    // The index may have been stored from any of the mirrors, when the first ones failed
+    let fresh = mirrors
+        .iter()
+        .filter_map(|mirror| cache.read(&index_json_url(mirror)))
+        .find(|(metadata, _)| !cache.refresh && metadata.age() < cache.ttl);
+    if let Some((metadata, body)) = fresh {
+        debug!(
+            "Using the stored index of {} from {:?}",
+            metadata.url, cache.path
+        );
+        return parse(&body);
+    }
+
+    let (last_mirror, fallback_mirrors) = mirrors
+        .split_last()
+        .expect("There's always at least one mirror");
+    for mirror in fallback_mirrors {
+        match list_from(mirror, cache) {
+            Ok(versions) => return Ok(versions),
+            Err(err) => debug!(
+                "Can't list the versions of {}, trying the next mirror: {}",
+                mirror, err
+            ),
+        }
+    }
+    list_from(last_mirror, cache)
+}
+
+fn index_json_url(base_url: &Url) -> String {
+    format!("{}/index.json", base_url)
+}
+
+fn list_from(base_url: &Url, cache: &IndexCache) -> Result<Vec<IndexedNodeVersion>, Error> {
+    let index_json_url = index_json_url(base_url);
+    let stored = cache.read(&index_json_url);
+
+    let mut headers = vec![];
+    if let Some((metadata, _)) = &stored {
+        if let Some(etag) = &metadata.etag {
+            headers.push(("If-None-Match", etag.as_str()));
+        }
+        if let Some(last_modified) = &metadata.last_modified {
+            headers.push(("If-Modified-Since", last_modified.as_str()));
+        }
+    }
+
+    debug!("Going to call for {}", &index_json_url);
+    let resp = crate::http::get_with_headers(&index_json_url, &headers)
+        .and_then(|resp| resp.error_for_status().map_err(crate::http::Error::from))
+        .context(HttpError)?;
+
+    if let (crate::http::StatusCode::NOT_MODIFIED, Some((mut metadata, body))) =
+        (resp.status(), stored)
+    {
+        debug!("The stored index is up to date with {}", base_url);
+        metadata.fetched_at = now_timestamp();
+        if let Err(err) = cache.write(&metadata, None) {
+            debug!("Can't update the stored index metadata: {}", err);
+        }
+        return parse(&body);
+    }
+
+    let header = |name: crate::http::header::HeaderName| {
+        resp.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+    let metadata = IndexMetadata {
+        url: index_json_url.clone(),
+        etag: header(crate::http::header::ETAG),
+        last_modified: header(crate::http::header::LAST_MODIFIED),
+        fetched_at: now_timestamp(),
+    };
+    let body = resp
+        .bytes()
+        .map_err(crate::http::Error::from)
+        .context(HttpError)?;
+    let value = parse(&body)?;
+    debug!("Listed the versions of {}", base_url);
+
+    if let Err(err) = cache.write(&metadata, Some(&body)) {
+        debug!("Can't store the index in {:?}: {}", cache.path, err);
+    }
+
+    Ok(value)
+}
+
+/// Lists the versions in the copy of `index.json` stored by [`list`], without network access
+pub fn list_offline(index_path: &Path) -> Result<Vec<IndexedNodeVersion>, Error> {
+    let body = match std::fs::read(index_path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Err(Error::NoCachedIndex),
+        other => other.context(CantReadCachedIndex { path: index_path })?,
+    };
+    parse(&body)
+}
+
+fn parse(body: &[u8]) -> Result<Vec<IndexedNodeVersion>, Error> {
+    let mut value: Vec<IndexedNodeVersion> =
+        serde_json::from_slice(body).context(CantParseIndex)?;
     value.sort_by(|a, b| a.version.cmp(&b.version));
     Ok(value)
 }
 
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("{}", source))]
+    HttpError { source: crate::http::Error },
+    #[snafu(display("Can't parse the Node.js versions index: {}", source))]
+    CantParseIndex { source: serde_json::Error },
+    #[snafu(display(
+        "Can't read the stored Node.js versions index at {:?}: {}",
+        path,
+        source
+    ))]
+    CantReadCachedIndex {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display(
+        "There's no stored copy of the Node.js versions index to use offline.\nRun `fnm ls-remote` while online to store one."
+    ))]
+    NoCachedIndex,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_mirror::TestMirror;
     use pretty_assertions::assert_eq;
 
+    fn index_cache(dir: &Path, ttl: Duration) -> IndexCache {
+        IndexCache {
+            path: dir.join("index.json"),
+            ttl,
+            refresh: false,
+        }
+    }
+
+    fn mirror_with_index(versions: &[&str]) -> TestMirror {
+        let mirror = TestMirror::start();
+        write_index(&mirror, versions);
+        mirror
+    }
+
+    fn write_index(mirror: &TestMirror, versions: &[&str]) {
+        let index: Vec<_> = versions
+            .iter()
+            .map(|version| {
+                serde_json::json!({ "version": version, "lts": false, "date": "2021-04-20", "files": [] })
+            })
+            .collect();
+        mirror.write_file("index.json", serde_json::Value::from(index).to_string());
+    }
+
+    fn versions(list: Vec<IndexedNodeVersion>) -> Vec<String> {
+        list.into_iter().map(|x| x.version.v_str()).collect()
+    }
+
     #[test]
     fn test_list() {
         let base_url = Url::parse("https://nodejs.org/dist").unwrap();
         let expected_version = Version::parse("12.0.0").unwrap();
-        let mut versions = list(&base_url).expect("Can't get HTTP data");
+        let index_dir = tempfile::tempdir().unwrap();
+        let cache = index_cache(index_dir.path(), Duration::ZERO);
+        let mut versions = list(&[base_url], &cache).expect("Can't get HTTP data");
         assert_eq!(
             versions
                 .drain(..)
@@ -97,4 +383,158 @@ mod tests {
             Some(expected_version)
         );
     }
+
+    #[test]
+    fn test_deserialize_metadata() {
+        let json = serde_json::json!([
+            {
+                "version": "v16.13.0",
+                "date": "2021-10-26",
+                "files": ["linux-x64", "osx-x64-tar"],
+                "npm": "8.1.0",
+                "v8": "9.4.146.19",
+                "uv": "1.42.0",
+                "zlib": "1.2.11",
+                "openssl": "1.1.1l+quic",
+                "modules": "93",
+                "lts": "Gallium",
+                "security": false
+            },
+            { "version": "v0.1.14", "date": "2011-08-26", "files": ["src"], "lts": false, "security": true }
+        ]);
+        let versions = parse(json.to_string().as_bytes()).expect("Can't parse index");
+
+        let old = &versions[0];
+        assert_eq!((old.npm.as_deref(), old.modules.as_deref()), (None, None));
+        assert!(old.security);
+
+        let gallium = &versions[1];
+        assert_eq!(gallium.npm.as_deref(), Some("8.1.0"));
+        assert_eq!(gallium.v8.as_deref(), Some("9.4.146.19"));
+        assert_eq!(gallium.uv.as_deref(), Some("1.42.0"));
+        assert_eq!(gallium.zlib.as_deref(), Some("1.2.11"));
+        assert_eq!(gallium.openssl.as_deref(), Some("1.1.1l+quic"));
+        assert_eq!(gallium.modules.as_deref(), Some("93"));
+        assert!(!gallium.security);
+    }
+
+    #[test]
+    fn test_builds() {
+        let json = serde_json::json!([{
+            "version": "v16.13.0",
+            "date": "2021-10-26",
+            "files": [
+                build_name(&Arch::X64, Libc::Glibc),
+                build_name(&Arch::Arm64, Libc::Glibc),
+                build_name(&Arch::X64, Libc::Musl),
+                "headers",
+                "src"
+            ],
+            "lts": "Gallium"
+        }]);
+        let version = &parse(json.to_string().as_bytes()).unwrap()[0];
+
+        assert!(version.has_build_for(&Arch::X64, Libc::Glibc));
+        assert!(!version.has_build_for(&Arch::Ppc64le, Libc::Glibc));
+        assert_eq!(version.available_arches(Libc::Glibc), vec!["x64", "arm64"]);
+
+        assert!(version.has_build_for(&Arch::X64, Libc::Musl));
+        assert!(!version.has_build_for(&Arch::Arm64, Libc::Musl));
+        assert_eq!(version.available_arches(Libc::Musl), vec!["x64"]);
+    }
+
+    #[test]
+    fn test_list_offline() {
+        let mirror = TestMirror::start();
+        mirror.write_file(
+            "index.json",
+            serde_json::json!([
+                { "version": "v16.0.0", "lts": false, "date": "2021-04-20", "files": [] },
+                { "version": "v14.17.0", "lts": "Fermium", "date": "2021-05-11", "files": [] },
+            ])
+            .to_string(),
+        );
+        let index_dir = tempfile::tempdir().unwrap();
+        let cache = index_cache(index_dir.path(), Duration::ZERO);
+
+        assert!(matches!(
+            list_offline(&cache.path),
+            Err(Error::NoCachedIndex)
+        ));
+
+        let online = list(&[mirror.url().clone()], &cache).expect("Can't list the index");
+        let offline = list_offline(&cache.path).expect("Can't list the stored index");
+        let versions = |list: Vec<IndexedNodeVersion>| -> Vec<_> {
+            list.into_iter().map(|x| (x.version, x.lts)).collect()
+        };
+        let expected = vec![
+            (
+                Version::parse("14.17.0").unwrap(),
+                Some("Fermium".to_string()),
+            ),
+            (Version::parse("16.0.0").unwrap(), None),
+        ];
+        assert_eq!(versions(online), expected);
+        assert_eq!(versions(offline), expected);
+    }
+
+    #[test]
+    fn test_list_reuses_index_within_ttl() {
+        let mirror = mirror_with_index(&["v16.0.0"]);
+        let index_dir = tempfile::tempdir().unwrap();
+        let mut cache = index_cache(index_dir.path(), Duration::from_hours(1));
+
+        list(&[mirror.url().clone()], &cache).unwrap();
+        write_index(&mirror, &["v16.0.0", "v16.1.0"]);
+        let stored = list(&[mirror.url().clone()], &cache).unwrap();
+        assert_eq!(versions(stored), vec!["v16.0.0"]);
+        assert_eq!(mirror.requests(), vec!["200 /dist/index.json"]);
+
+        cache.refresh = true;
+        let refreshed = list(&[mirror.url().clone()], &cache).unwrap();
+        assert_eq!(versions(refreshed), vec!["v16.0.0", "v16.1.0"]);
+    }
+
+    #[test]
+    fn test_list_revalidates_expired_index() {
+        let mirror = mirror_with_index(&["v16.0.0"]);
+        let index_dir = tempfile::tempdir().unwrap();
+        let cache = index_cache(index_dir.path(), Duration::ZERO);
+
+        list(&[mirror.url().clone()], &cache).unwrap();
+        let revalidated = list(&[mirror.url().clone()], &cache).unwrap();
+        assert_eq!(versions(revalidated), vec!["v16.0.0"]);
+
+        write_index(&mirror, &["v16.0.0", "v16.1.0"]);
+        let updated = list(&[mirror.url().clone()], &cache).unwrap();
+        assert_eq!(versions(updated), vec!["v16.0.0", "v16.1.0"]);
+
+        assert_eq!(
+            mirror.requests(),
+            vec![
+                "200 /dist/index.json",
+                "304 /dist/index.json",
+                "200 /dist/index.json"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_falls_back_to_next_mirror() {
+        let empty_mirror = TestMirror::start();
+        let mirror = mirror_with_index(&["v16.0.0"]);
+        let mirrors = [empty_mirror.url().clone(), mirror.url().clone()];
+        let index_dir = tempfile::tempdir().unwrap();
+        let cache = index_cache(index_dir.path(), Duration::from_hours(1));
+
+        let listed = list(&mirrors, &cache).unwrap();
+        assert_eq!(versions(listed), vec!["v16.0.0"]);
+        assert_eq!(empty_mirror.requests(), vec!["404 /dist/index.json"]);
+
+        // The index stored from the second mirror is reused without asking the first one again
+        let stored = list(&mirrors, &cache).unwrap();
+        assert_eq!(versions(stored), vec!["v16.0.0"]);
+        assert_eq!(empty_mirror.requests(), vec!["404 /dist/index.json"]);
+        assert_eq!(mirror.requests(), vec!["200 /dist/index.json"]);
+    }
 }
@@ -1,7 +1,7 @@
 use crate::version_file_strategy::VersionFileStrategy;
 
 use super::shell::Shell;
-use indoc::{formatdoc, indoc};
+use indoc::formatdoc;
 use std::path::Path;
 
 #[derive(Debug)]
@@ -21,15 +21,22 @@ impl Shell for Bash {
     }
 
     fn use_on_cd(&self, config: &crate::config::FnmConfig) -> String {
+        let condition = config
+            .use_on_cd_files()
+            .iter()
+            .map(|file| format!("-f '{}'", file.replace('\'', r"'\''")))
+            .collect::<Vec<_>>()
+            .join(" || ");
         let autoload_hook = match config.version_file_strategy() {
-            VersionFileStrategy::Local => indoc!(
+            VersionFileStrategy::Local => formatdoc!(
                 r#"
-                    if [[ -f .node-version || -f .nvmrc ]]; then
+                    if [[ {condition} ]]; then
                         fnm use --silent-if-unchanged
                     fi
-                "#
+                "#,
+                condition = condition
             ),
-            VersionFileStrategy::Recursive => r#"fnm use --silent-if-unchanged"#,
+            VersionFileStrategy::Recursive => String::from("fnm use --silent-if-unchanged"),
         };
         formatdoc!(
             r#"
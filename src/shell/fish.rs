@@ -1,7 +1,7 @@
 use crate::version_file_strategy::VersionFileStrategy;
 
 use super::shell::Shell;
-use indoc::{formatdoc, indoc};
+use indoc::formatdoc;
 use std::path::Path;
 
 #[derive(Debug)]
@@ -21,15 +21,25 @@ impl Shell for Fish {
     }
 
     fn use_on_cd(&self, config: &crate::config::FnmConfig) -> String {
+        let condition = config
+            .use_on_cd_files()
+            .iter()
+            .map(|file| {
+                let file = file.replace('\\', r"\\").replace('\'', r"\'");
+                format!("-f '{}'", file)
+            })
+            .collect::<Vec<_>>()
+            .join(" -o ");
         let autoload_hook = match config.version_file_strategy() {
-            VersionFileStrategy::Local => indoc!(
+            VersionFileStrategy::Local => formatdoc!(
                 r#"
-                    if test -f .node-version -o -f .nvmrc
+                    if test {condition}
                         fnm use --silent-if-unchanged
                     end
-                "#
+                "#,
+                condition = condition
             ),
-            VersionFileStrategy::Recursive => r#"fnm use --silent-if-unchanged"#,
+            VersionFileStrategy::Recursive => String::from("fnm use --silent-if-unchanged"),
         };
         formatdoc!(
             r#"
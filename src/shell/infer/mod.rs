@@ -24,7 +24,7 @@ pub fn infer_shell() -> Option<Box<dyn Shell>> {
                 Some("pwsh" | "powershell") => return Some(Box::from(PowerShell)),
                 Some("cmd") => return Some(Box::from(WindowsCmd)),
                 cmd_name => debug!("binary is not a supported shell: {:?}", cmd_name),
-            };
+            }
         } else {
             current_pid = None;
         }
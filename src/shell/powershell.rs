@@ -1,7 +1,7 @@
 use crate::version_file_strategy::VersionFileStrategy;
 
 use super::Shell;
-use indoc::{formatdoc, indoc};
+use indoc::formatdoc;
 use std::path::Path;
 
 #[derive(Debug)]
@@ -21,13 +21,20 @@ impl Shell for PowerShell {
     }
 
     fn use_on_cd(&self, config: &crate::config::FnmConfig) -> String {
+        let condition = config
+            .use_on_cd_files()
+            .iter()
+            .map(|file| format!("(Test-Path '{}')", file.replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join(" -Or ");
         let autoload_hook = match config.version_file_strategy() {
-            VersionFileStrategy::Local => indoc!(
+            VersionFileStrategy::Local => formatdoc!(
                 r#"
-                    If ((Test-Path .nvmrc) -Or (Test-Path .node-version)) { & fnm use --silent-if-unchanged }
-                "#
+                    If ({condition}) {{ & fnm use --silent-if-unchanged }}
+                "#,
+                condition = condition
             ),
-            VersionFileStrategy::Recursive => r#"fnm use --silent-if-unchanged"#,
+            VersionFileStrategy::Recursive => String::from("fnm use --silent-if-unchanged"),
         };
         formatdoc!(
             r#"
@@ -1,14 +1,19 @@
 @echo off
 cd %1
-if "%FNM_VERSION_FILE_STRATEGY%" == "recursive" (
-  fnm use --silent-if-unchanged
-) else (
-  if exist .nvmrc (
-    fnm use --silent-if-unchanged
-  ) else (
-    if exist .node-version (
-      fnm use --silent-if-unchanged
-    )
-  )
+set __fnm_found=
+if "%FNM_VERSION_FILE_STRATEGY%" == "recursive" set __fnm_found=1
+if not "%FNM_VERSION_SOURCES:version-files=%" == "%FNM_VERSION_SOURCES%" (
+  for %%f in (%FNM_VERSION_FILES%) do if exist "%%~f" set __fnm_found=1
 )
+if not "%FNM_VERSION_SOURCES:tool-versions=%" == "%FNM_VERSION_SOURCES%" (
+  if exist .tool-versions set __fnm_found=1
+)
+if not "%FNM_VERSION_SOURCES:volta=%" == "%FNM_VERSION_SOURCES%" (
+  if exist package.json set __fnm_found=1
+)
+if not "%FNM_VERSION_SOURCES:engines=%" == "%FNM_VERSION_SOURCES%" (
+  if exist package.json set __fnm_found=1
+)
+if defined __fnm_found fnm use --silent-if-unchanged
+set __fnm_found=
 @echo on
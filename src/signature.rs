@@ -0,0 +1,220 @@
+//! Verifies the GPG signatures Node.js releases publish for their `SHASUMS256.txt` files.
+//!
+//! The actual verification is delegated to `gpgv`, which only ever trusts the keys
+//! in the keyring it is given, unlike `gpg` which would consult the user's keyring.
+
+use log::debug;
+use snafu::{ensure, ResultExt, Snafu};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tempfile::NamedTempFile;
+
+/// The Node.js release keys, generated by `.ci/update-release-keys.sh`
+const BUNDLED_KEYRING: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/release-keys.gpg"));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Keyring {
+    /// The Node.js release keys bundled into the fnm binary
+    Bundled,
+    /// A binary keyring, as exported by `gpg --export`
+    Path(PathBuf),
+}
+
+impl Keyring {
+    fn materialize(&self) -> Result<KeyringFile, Error> {
+        match self {
+            Self::Path(path) => {
+                ensure!(path.exists(), KeyringNotFound { path: path.clone() });
+                Ok(KeyringFile::Existing(path.clone()))
+            }
+            Self::Bundled => {
+                ensure!(!BUNDLED_KEYRING.is_empty(), BundledKeyringMissing);
+                let mut file = NamedTempFile::new().context(IoError)?;
+                file.write_all(BUNDLED_KEYRING).context(IoError)?;
+                Ok(KeyringFile::Temporary(file))
+            }
+        }
+    }
+}
+
+enum KeyringFile {
+    Existing(PathBuf),
+    Temporary(NamedTempFile),
+}
+
+impl KeyringFile {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Existing(path) => path,
+            Self::Temporary(file) => file.path(),
+        }
+    }
+}
+
+/// Verifies a detached signature (`SHASUMS256.txt.sig`) of `data`
+pub fn verify_detached(data: &[u8], signature: &[u8], keyring: &Keyring) -> Result<(), Error> {
+    let data_file = temp_file_with(data)?;
+    let signature_file = temp_file_with(signature)?;
+    run_gpgv(keyring, &[signature_file.path(), data_file.path()])
+}
+
+/// Verifies a clearsigned document (`SHASUMS256.txt.asc`) and returns the signed text
+pub fn verify_clearsigned(document: &[u8], keyring: &Keyring) -> Result<String, Error> {
+    let document_file = temp_file_with(document)?;
+    let output_dir = tempfile::tempdir().context(IoError)?;
+    let output_path = output_dir.path().join("signed.txt");
+    run_gpgv(
+        keyring,
+        &[Path::new("--output"), &output_path, document_file.path()],
+    )?;
+    std::fs::read_to_string(output_path).context(IoError)
+}
+
+fn run_gpgv(keyring: &Keyring, args: &[&Path]) -> Result<(), Error> {
+    let keyring_file = keyring.materialize()?;
+    let keyring_path = std::fs::canonicalize(keyring_file.path()).context(IoError)?;
+    // Use an empty home directory so gpgv won't read or create anything in the user's `~/.gnupg`
+    let home_dir = tempfile::tempdir().context(IoError)?;
+
+    debug!("Verifying signature with keyring {:?}", keyring_path);
+    let output = Command::new("gpgv")
+        .arg("--homedir")
+        .arg(home_dir.path())
+        .arg("--keyring")
+        .arg(&keyring_path)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .context(GpgvNotFound)?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    debug!("gpgv output: {}", stderr);
+    ensure!(
+        output.status.success(),
+        InvalidSignature { details: stderr }
+    );
+
+    Ok(())
+}
+
+fn temp_file_with(contents: &[u8]) -> Result<NamedTempFile, Error> {
+    let mut file = NamedTempFile::new().context(IoError)?;
+    file.write_all(contents).context(IoError)?;
+    Ok(file)
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Can't run `gpgv`, which is required to verify signatures: {}", source))]
+    GpgvNotFound {
+        source: std::io::Error,
+    },
+    #[snafu(display("The signature of the checksums file is invalid:\n{}", details))]
+    InvalidSignature {
+        details: String,
+    },
+    #[snafu(display("Can't find the keyring at {:?}", path))]
+    KeyringNotFound {
+        path: PathBuf,
+    },
+    #[snafu(display(
+        "This build of fnm doesn't bundle the Node.js release keys. Please provide a keyring using `--release-keyring`."
+    ))]
+    BundledKeyringMissing,
+    IoError {
+        source: std::io::Error,
+    },
+}
+
+#[cfg(all(test, unix))]
+pub(crate) mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// A throwaway GPG key, used to sign fixtures in tests
+    pub struct TestKey {
+        home_dir: tempfile::TempDir,
+    }
+
+    impl TestKey {
+        pub fn generate() -> Self {
+            let home_dir = tempfile::tempdir().unwrap();
+            let key = Self { home_dir };
+            key.gpg(&[
+                "--passphrase",
+                "",
+                "--quick-generate-key",
+                "fnm test <test@fnm.invalid>",
+                "ed25519",
+                "sign",
+                "never",
+            ]);
+            key
+        }
+
+        pub fn export_keyring(&self, path: &Path) {
+            let exported = self.gpg(&["--export"]);
+            std::fs::write(path, exported).unwrap();
+        }
+
+        pub fn sign_detached(&self, data: &[u8]) -> Vec<u8> {
+            self.sign("--detach-sign", data)
+        }
+
+        pub fn clearsign(&self, data: &[u8]) -> Vec<u8> {
+            self.sign("--clearsign", data)
+        }
+
+        fn sign(&self, mode: &str, data: &[u8]) -> Vec<u8> {
+            let data_file = temp_file_with(data).unwrap();
+            self.gpg(&[mode, "--output", "-", data_file.path().to_str().unwrap()])
+        }
+
+        fn gpg(&self, args: &[&str]) -> Vec<u8> {
+            let mut all_args = vec!["--batch", "--pinentry-mode", "loopback"];
+            all_args.extend(args);
+            duct::cmd("gpg", all_args)
+                .env("GNUPGHOME", self.home_dir.path())
+                .stdout_capture()
+                .stderr_capture()
+                .run()
+                .expect("Can't run gpg")
+                .stdout
+        }
+    }
+
+    #[test]
+    fn test_verify_detached() {
+        let key = TestKey::generate();
+        let keyring_dir = tempfile::tempdir().unwrap();
+        let keyring = keyring_dir.path().join("keyring.gpg");
+        key.export_keyring(&keyring);
+        let data = b"some checksums";
+        let signature = key.sign_detached(data);
+
+        verify_detached(data, &signature, &Keyring::Path(keyring.clone()))
+            .expect("Valid signature was rejected");
+
+        let result = verify_detached(b"tampered checksums", &signature, &Keyring::Path(keyring));
+        assert!(matches!(result, Err(Error::InvalidSignature { .. })));
+    }
+
+    #[test]
+    fn test_verify_clearsigned() {
+        let key = TestKey::generate();
+        let keyring_dir = tempfile::tempdir().unwrap();
+        let keyring = keyring_dir.path().join("keyring.gpg");
+        key.export_keyring(&keyring);
+        let document = key.clearsign(b"some checksums\n");
+
+        let signed_text = verify_clearsigned(&document, &Keyring::Path(keyring.clone()))
+            .expect("Valid signature was rejected");
+        assert_eq!(signed_text, "some checksums\n");
+
+        let other_key = TestKey::generate();
+        let other_document = other_key.clearsign(b"some checksums\n");
+        let result = verify_clearsigned(&other_document, &Keyring::Path(keyring));
+        assert!(matches!(result, Err(Error::InvalidSignature { .. })));
+    }
+}
@@ -0,0 +1,208 @@
+//! Builds Node from its source code, for platforms and libc variants without prebuilt binaries.
+//!
+//! The source is built with the usual `./configure && make install`, so the build
+//! needs the same toolchain as building Node by hand: a C++ compiler, `make` and Python.
+
+use crate::version::Version;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt, Snafu};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// The file in an installation directory that describes how it was built
+const METADATA_FILENAME: &str = "build.json";
+
+/// How many lines of the build output are shown when a build step fails
+const FAILURE_OUTPUT_LINES: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOptions {
+    /// The number of parallel `make` jobs
+    pub jobs: usize,
+    /// Additional flags for `./configure`
+    pub configure_flags: Vec<String>,
+    /// Show the output of the build on stderr, instead of only when it fails
+    pub show_output: bool,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            jobs: default_jobs(),
+            configure_flags: vec![],
+            show_output: false,
+        }
+    }
+}
+
+/// One job per available CPU
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+}
+
+/// Builds the source in `source_dir` and installs it into `prefix`
+pub fn build(source_dir: &Path, prefix: &Path, options: &BuildOptions) -> Result<(), Error> {
+    ensure!(cfg!(unix), UnsupportedPlatform);
+
+    let mut configure = Command::new(source_dir.join("configure"));
+    configure
+        .arg(format!("--prefix={}", prefix.display()))
+        .args(&options.configure_flags);
+    run("configure", configure, source_dir, options)?;
+
+    let make_program = std::env::var_os("MAKE").unwrap_or_else(|| "make".into());
+    let mut make = Command::new(make_program);
+    make.arg(format!("-j{}", options.jobs)).arg("install");
+    run("make", make, source_dir, options)
+}
+
+fn run(
+    step: &'static str,
+    mut command: Command,
+    source_dir: &Path,
+    options: &BuildOptions,
+) -> Result<(), Error> {
+    command.current_dir(source_dir).stdin(Stdio::null());
+    debug!("Running {:?}", command);
+
+    if options.show_output {
+        // The build output goes to stderr, so it doesn't end up in the output of scripts
+        let status = command
+            .stdout(std::io::stderr())
+            .status()
+            .context(CantRunStep { step })?;
+        ensure!(
+            status.success(),
+            StepFailed {
+                step,
+                status: status.to_string(),
+                output: String::new(),
+            }
+        );
+        return Ok(());
+    }
+
+    let output = command.output().context(CantRunStep { step })?;
+    ensure!(
+        output.status.success(),
+        StepFailed {
+            step,
+            status: output.status.to_string(),
+            output: output_tail(&output),
+        }
+    );
+    Ok(())
+}
+
+/// The last lines of the output of a failed build step
+fn output_tail(output: &Output) -> String {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<_> = stdout.lines().chain(stderr.lines()).collect();
+    let tail = &lines[lines.len().saturating_sub(FAILURE_OUTPUT_LINES)..];
+    tail.iter().fold(String::new(), |mut output, line| {
+        output.push_str("\n  ");
+        output.push_str(line);
+        output
+    })
+}
+
+/// How an installation was built from source, stored next to the installation
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BuildMetadata {
+    pub version: String,
+    pub platform: String,
+    pub arch: String,
+    pub configure_flags: Vec<String>,
+    pub jobs: usize,
+    pub built_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl BuildMetadata {
+    pub fn new(version: &Version, options: &BuildOptions) -> Self {
+        Self {
+            version: version.v_str(),
+            platform: crate::system_info::platform_name().to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            configure_flags: options.configure_flags.clone(),
+            jobs: options.jobs,
+            built_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Writes the metadata into the installation directory `version_dir`
+    pub fn write(&self, version_dir: &Path) -> Result<(), Error> {
+        let path = version_dir.join(METADATA_FILENAME);
+        let json = serde_json::to_vec_pretty(self).context(CantSerializeMetadata)?;
+        std::fs::write(&path, json).context(CantWriteMetadata { path })
+    }
+
+    /// Reads the metadata of an installation, if it was built from source
+    #[cfg(test)]
+    pub fn read(version_dir: &Path) -> Option<Self> {
+        let json = std::fs::read(version_dir.join(METADATA_FILENAME)).ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Building Node from source is only supported on Linux and macOS"))]
+    UnsupportedPlatform,
+    #[snafu(display("Can't run {}: {}", step, source))]
+    CantRunStep {
+        step: &'static str,
+        source: std::io::Error,
+    },
+    #[snafu(display("{} failed with {}{}", step, status, output))]
+    StepFailed {
+        step: &'static str,
+        status: String,
+        output: String,
+    },
+    #[snafu(display("Can't serialize the build metadata: {}", source))]
+    CantSerializeMetadata { source: serde_json::Error },
+    #[snafu(display("Can't write the build metadata to {:?}: {}", path, source))]
+    CantWriteMetadata {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_step_shows_output() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let configure = source_dir.path().join("configure");
+        std::fs::write(
+            &configure,
+            "#!/bin/sh\necho checking for a compiler\nexit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(
+            &configure,
+            std::os::unix::fs::PermissionsExt::from_mode(0o755),
+        )
+        .unwrap();
+
+        let result = build(
+            source_dir.path(),
+            &source_dir.path().join("prefix"),
+            &BuildOptions::default(),
+        );
+        match result {
+            Err(
+                err @ Error::StepFailed {
+                    step: "configure", ..
+                },
+            ) => {
+                assert!(err.to_string().ends_with("\n  checking for a compiler"));
+            }
+            other => panic!("Expected configure to fail, got {:?}", other),
+        }
+    }
+}
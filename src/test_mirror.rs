@@ -0,0 +1,485 @@
+//! A fake Node.js distribution mirror served over HTTP from a temporary directory,
+//! so downloading and installing can be tested without network access.
+//! It can also be served over HTTPS, and act as an HTTP proxy.
+
+#[cfg(unix)]
+use crate::archive::ArchiveFormat;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+use url::Url;
+
+pub struct TestMirror {
+    root: TempDir,
+    url: Url,
+    state: Arc<State>,
+}
+
+/// The state shared with the threads serving the requests
+struct State {
+    served_dir: PathBuf,
+    requests: Mutex<Vec<String>>,
+    faults: Mutex<VecDeque<Fault>>,
+    authorization: Mutex<Option<String>>,
+}
+
+/// A failure of the mirror, to test how flaky mirrors are handled
+#[derive(Debug, Clone, Copy)]
+pub enum Fault {
+    /// Responds with `503 Service Unavailable`
+    ServiceUnavailable,
+    /// Sends half of the body and closes the connection
+    Disconnect,
+    /// Sends half of the body and stops responding for the given duration
+    Stall(Duration),
+}
+
+trait Stream: Read + Write + Send {}
+impl<T: Read + Write + Send> Stream for T {}
+
+impl TestMirror {
+    pub fn start() -> Self {
+        Self::start_with("http", "127.0.0.1", |stream| Some(Box::new(stream)))
+    }
+
+    /// Serves the mirror over HTTPS as `localhost`, with a certificate signed by the
+    /// certificate authority of `certificates`. With `require_client_cert`, clients must
+    /// authenticate with a certificate signed by it as well.
+    #[cfg(unix)]
+    pub fn start_tls(certificates: &TestCertificates, require_client_cert: bool) -> Self {
+        let config = Arc::new(certificates.server_config(require_client_cert));
+        Self::start_with("https", "localhost", move |stream| {
+            let connection = rustls::ServerConnection::new(Arc::clone(&config)).ok()?;
+            Some(Box::new(rustls::StreamOwned::new(connection, stream)))
+        })
+    }
+
+    fn start_with(
+        scheme: &str,
+        host: &str,
+        accept: impl Fn(TcpStream) -> Option<Box<dyn Stream>> + Send + 'static,
+    ) -> Self {
+        let root = tempfile::tempdir().expect("Can't create a temp directory");
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Can't bind a local port");
+        let port = listener
+            .local_addr()
+            .expect("Can't read local address")
+            .port();
+        let url = Url::parse(&format!("{}://{}:{}/dist", scheme, host, port)).unwrap();
+        let state = Arc::new(State {
+            served_dir: root.path().to_path_buf(),
+            requests: Mutex::new(vec![]),
+            faults: Mutex::new(VecDeque::new()),
+            authorization: Mutex::new(None),
+        });
+        let served_state = Arc::clone(&state);
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let Some(stream) = accept(stream) else {
+                    continue;
+                };
+                let state = Arc::clone(&served_state);
+                let fault = state.faults.lock().unwrap().pop_front();
+                std::thread::spawn(move || serve(stream, &state, fault));
+            }
+        });
+
+        Self { root, url, state }
+    }
+
+    /// Makes the next requests fail with the given faults, one fault per request
+    pub fn inject_faults(&self, faults: impl IntoIterator<Item = Fault>) {
+        self.state.faults.lock().unwrap().extend(faults);
+    }
+
+    /// Responds with `401 Unauthorized` to requests without this `Authorization` header
+    pub fn require_authorization(&self, authorization: &str) {
+        *self.state.authorization.lock().unwrap() = Some(authorization.to_string());
+    }
+
+    /// The requests served so far, as `<status> <path>`
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn path(&self) -> &Path {
+        self.root.path()
+    }
+
+    pub fn write_file(&self, relative_path: &str, contents: impl AsRef<[u8]>) {
+        let path = self.root.path().join(relative_path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// Publishes a release containing a `bin/node` script that prints its version,
+    /// along with a matching `SHASUMS256.txt`
+    #[cfg(unix)]
+    pub fn add_release(&self, version: &str, arch: &str) {
+        self.add_release_as(version, arch, ArchiveFormat::TarXz);
+    }
+
+    /// Like [`TestMirror::add_release`], publishing an archive of the given format
+    #[cfg(unix)]
+    pub fn add_release_as(&self, version: &str, arch: &str, format: ArchiveFormat) {
+        let dirname = format!(
+            "node-{}-{}-{}",
+            version,
+            crate::system_info::platform_name(),
+            arch
+        );
+        let filename = format!("{}.{}", dirname, format.extension());
+        let script = format!("#!/bin/sh\necho {}\n", version);
+        let archive = build_tar(&[(format!("{}/bin/node", dirname), script)], format);
+        self.publish_archive(version, &filename, &archive);
+    }
+
+    /// Publishes the source code of a release, which "builds" a `bin/node` script
+    /// that prints its version with `./configure && make install`
+    #[cfg(unix)]
+    pub fn add_source(&self, version: &str) {
+        let dirname = format!("node-{}", version);
+        let configure = indoc::indoc! {r#"
+            #!/bin/sh
+            for arg in "$@"; do
+              case "$arg" in
+                --prefix=*) echo "PREFIX = ${arg#--prefix=}" > config.mk ;;
+                *) echo "$arg" >> configure-flags.txt ;;
+              esac
+            done
+        "#};
+        let makefile = format!(
+            "include config.mk\n\ninstall:\n\tmkdir -p $(PREFIX)/bin\n\tprintf '#!/bin/sh\\necho {}\\n' > $(PREFIX)/bin/node\n\tchmod +x $(PREFIX)/bin/node\n",
+            version
+        );
+        let archive = build_tar(
+            &[
+                (format!("{}/configure", dirname), configure.to_string()),
+                (format!("{}/Makefile", dirname), makefile),
+            ],
+            ArchiveFormat::TarXz,
+        );
+        self.publish_archive(version, &format!("{}.tar.xz", dirname), &archive);
+    }
+
+    /// Writes an archive of a release, and adds its checksum to the `SHASUMS256.txt`
+    #[cfg(unix)]
+    fn publish_archive(&self, version: &str, filename: &str, archive: &[u8]) {
+        let checksum = {
+            let mut reader = crate::checksum::Sha256Reader::new(archive);
+            std::io::copy(&mut reader, &mut std::io::sink()).unwrap();
+            reader.hex_digest()
+        };
+
+        self.write_file(&format!("{}/{}", version, filename), archive);
+
+        let shasums_path = format!("{}/SHASUMS256.txt", version);
+        let mut shasums =
+            std::fs::read_to_string(self.root.path().join(&shasums_path)).unwrap_or_default();
+        writeln!(shasums, "{}  {}", checksum, filename).unwrap();
+        self.write_file(&shasums_path, shasums);
+    }
+}
+
+/// Builds an archive of executable files
+#[cfg(unix)]
+fn build_tar(files: &[(String, String)], format: ArchiveFormat) -> Vec<u8> {
+    let mut builder = tar::Builder::new(vec![]);
+    for (path, contents) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, contents.as_bytes())
+            .unwrap();
+    }
+    let tar = builder.into_inner().unwrap();
+
+    match format {
+        ArchiveFormat::TarXz => {
+            let mut encoder = xz2::write::XzEncoder::new(vec![], 6);
+            encoder.write_all(&tar).unwrap();
+            encoder.finish().unwrap()
+        }
+        ArchiveFormat::TarGz => {
+            let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+            encoder.write_all(&tar).unwrap();
+            encoder.finish().unwrap()
+        }
+        ArchiveFormat::TarZst => zstd::encode_all(tar.as_slice(), 0).unwrap(),
+    }
+}
+
+/// The parts of a request the mirror responds to
+#[derive(Default)]
+struct Request {
+    path: String,
+    if_none_match: Option<String>,
+    range_start: Option<usize>,
+    authorization: Option<String>,
+}
+
+fn read_request(stream: &mut impl Read) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+
+    let mut path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    // Requests sent through a proxy have an absolute URL
+    if let Some(url) = path.strip_prefix("http://") {
+        path = url.find('/').map_or("/", |start| &url[start..]);
+    }
+    let mut request = Request {
+        path: path.to_string(),
+        ..Request::default()
+    };
+
+    let mut header = String::new();
+    while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("if-none-match") {
+                request.if_none_match = Some(value.to_string());
+            } else if name.eq_ignore_ascii_case("range") {
+                request.range_start = value
+                    .strip_prefix("bytes=")
+                    .and_then(|range| range.strip_suffix('-'))
+                    .and_then(|start| start.parse().ok());
+            } else if name.eq_ignore_ascii_case("authorization") {
+                request.authorization = Some(value.to_string());
+            }
+        }
+        header.clear();
+    }
+
+    Some(request)
+}
+
+fn serve(mut stream: Box<dyn Stream>, state: &State, fault: Option<Fault>) {
+    let Some(request) = read_request(&mut stream) else {
+        return;
+    };
+    let path = &request.path;
+    let file_path: PathBuf = path
+        .trim_start_matches("/dist")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != "..")
+        .fold(state.served_dir.clone(), |acc, part| acc.join(part));
+
+    let mut headers = String::new();
+    let (status, reason, body) = match std::fs::read(&file_path) {
+        _ if matches!(fault, Some(Fault::ServiceUnavailable)) => {
+            (503, "Service Unavailable", b"Unavailable".to_vec())
+        }
+        _ if state
+            .authorization
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|required| request.authorization.as_ref() != Some(required)) =>
+        {
+            (401, "Unauthorized", b"Unauthorized".to_vec())
+        }
+        Ok(body) if file_path.is_file() => {
+            let etag = {
+                let mut reader = crate::checksum::Sha256Reader::new(body.as_slice());
+                std::io::copy(&mut reader, &mut std::io::sink()).unwrap();
+                format!("\"{}\"", reader.hex_digest())
+            };
+            writeln!(headers, "ETag: {}\r", etag).unwrap();
+            match request.range_start {
+                _ if request.if_none_match.as_ref() == Some(&etag) => (304, "Not Modified", vec![]),
+                Some(start) if start >= body.len() => (416, "Range Not Satisfiable", vec![]),
+                Some(start) => {
+                    writeln!(
+                        headers,
+                        "Content-Range: bytes {}-{}/{}\r",
+                        start,
+                        body.len() - 1,
+                        body.len()
+                    )
+                    .unwrap();
+                    (206, "Partial Content", body[start..].to_vec())
+                }
+                None => (200, "OK", body),
+            }
+        }
+        _ => (404, "Not Found", b"Not Found".to_vec()),
+    };
+
+    state
+        .requests
+        .lock()
+        .unwrap()
+        .push(format!("{} {}", status, path));
+
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason,
+        headers,
+        body.len()
+    );
+    match fault {
+        Some(Fault::Disconnect) => {
+            let _ = stream.write_all(&body[..body.len() / 2]);
+        }
+        Some(Fault::Stall(duration)) => {
+            let _ = stream.write_all(&body[..body.len() / 2]);
+            let _ = stream.flush();
+            std::thread::sleep(duration);
+        }
+        _ => {
+            let _ = stream.write_all(&body);
+        }
+    }
+    let _ = stream.flush();
+}
+
+/// A certificate authority with a certificate for `localhost` and a client certificate,
+/// generated with `openssl`
+#[cfg(unix)]
+pub struct TestCertificates {
+    dir: TempDir,
+}
+
+#[cfg(unix)]
+impl TestCertificates {
+    pub fn generate() -> Self {
+        let dir = tempfile::tempdir().expect("Can't create a temp directory");
+        let openssl = |args: &[&str]| {
+            let output = std::process::Command::new("openssl")
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .expect("Can't run openssl");
+            assert!(
+                output.status.success(),
+                "openssl failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        };
+        let new_key = ["-newkey", "ec", "-pkeyopt", "ec_paramgen_curve:prime256v1"];
+
+        openssl(
+            &[
+                &[
+                    "req",
+                    "-x509",
+                    "-nodes",
+                    "-days",
+                    "1",
+                    "-subj",
+                    "/CN=fnm test CA",
+                ][..],
+                &new_key,
+                &["-keyout", "ca.key", "-out", "ca.pem"],
+                &["-addext", "basicConstraints=critical,CA:TRUE"],
+                &["-addext", "keyUsage=critical,keyCertSign,cRLSign"],
+            ]
+            .concat(),
+        );
+
+        let certificates = [
+            (
+                "server",
+                "localhost",
+                "subjectAltName=DNS:localhost\nextendedKeyUsage=serverAuth\n",
+            ),
+            ("client", "fnm test client", "extendedKeyUsage=clientAuth\n"),
+        ];
+        for (name, common_name, extensions) in certificates {
+            let (key, csr, pem, ext) = (
+                format!("{}.key", name),
+                format!("{}.csr", name),
+                format!("{}.pem", name),
+                format!("{}.ext", name),
+            );
+            std::fs::write(dir.path().join(&ext), extensions).unwrap();
+            let subject = format!("/CN={}", common_name);
+            openssl(
+                &[
+                    &["req", "-nodes", "-subj", &subject][..],
+                    &new_key,
+                    &["-keyout", &key, "-out", &csr],
+                ]
+                .concat(),
+            );
+            openssl(&[
+                "x509",
+                "-req",
+                "-days",
+                "1",
+                "-in",
+                &csr,
+                "-CA",
+                "ca.pem",
+                "-CAkey",
+                "ca.key",
+                "-CAcreateserial",
+                "-extfile",
+                &ext,
+                "-out",
+                &pem,
+            ]);
+        }
+
+        Self { dir }
+    }
+
+    pub fn ca_cert(&self) -> PathBuf {
+        self.dir.path().join("ca.pem")
+    }
+
+    pub fn client_cert(&self) -> PathBuf {
+        self.dir.path().join("client.pem")
+    }
+
+    pub fn client_key(&self) -> PathBuf {
+        self.dir.path().join("client.key")
+    }
+
+    fn server_config(&self, require_client_cert: bool) -> rustls::ServerConfig {
+        let read_certs = |name: &str| -> Vec<rustls::Certificate> {
+            let pem = std::fs::read(self.dir.path().join(name)).unwrap();
+            rustls_pemfile::certs(&mut pem.as_slice())
+                .unwrap()
+                .into_iter()
+                .map(rustls::Certificate)
+                .collect()
+        };
+        let key = {
+            let pem = std::fs::read(self.dir.path().join("server.key")).unwrap();
+            let mut keys = rustls_pemfile::pkcs8_private_keys(&mut pem.as_slice()).unwrap();
+            rustls::PrivateKey(keys.remove(0))
+        };
+
+        let client_cert_verifier = if require_client_cert {
+            let mut roots = rustls::RootCertStore::empty();
+            for cert in read_certs("ca.pem") {
+                roots.add(&cert).unwrap();
+            }
+            rustls::server::AllowAnyAuthenticatedClient::new(roots)
+        } else {
+            rustls::server::NoClientAuth::new()
+        };
+
+        rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_client_cert_verifier(client_cert_verifier)
+            .with_single_cert(read_certs("server.pem"), key)
+            .expect("Can't configure TLS")
+    }
+}
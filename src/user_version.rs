@@ -1,4 +1,5 @@
 use crate::version::Version;
+use crate::version_range::VersionRange;
 use std::str::FromStr;
 
 #[derive(Clone, Debug)]
@@ -6,6 +7,10 @@ pub enum UserVersion {
     OnlyMajor(u64),
     MajorMinor(u64, u64),
     Full(Version),
+    /// An npm-style range, like `>=16.13 <17` or `14.x || 16.x`
+    Range(VersionRange),
+    /// The newest release, written `node`, `stable` or `latest` like in nvm
+    Latest,
 }
 
 impl UserVersion {
@@ -42,6 +47,8 @@ impl UserVersion {
                 }
             }
             (_, Version::Bypassed | Version::Lts(_) | Version::Alias(_)) => false,
+            (Self::Range(range), Version::Semver(other)) => range.matches(other),
+            (Self::Latest, Version::Semver(_)) => true,
             (Self::OnlyMajor(major), Version::Semver(other)) => *major == other.major,
             (Self::MajorMinor(major, minor), Version::Semver(other)) => {
                 *major == other.major && *minor == other.minor
@@ -61,6 +68,8 @@ impl std::fmt::Display for UserVersion {
             Self::Full(x) => x.fmt(f),
             Self::OnlyMajor(major) => write!(f, "v{}.x.x", major),
             Self::MajorMinor(major, minor) => write!(f, "v{}.{}.x", major, minor),
+            Self::Range(range) => range.fmt(f),
+            Self::Latest => write!(f, "latest"),
         }
     }
 }
@@ -72,6 +81,15 @@ fn skip_first_v(str: &str) -> &str {
 impl FromStr for UserVersion {
     type Err = semver::Error;
     fn from_str(s: &str) -> Result<UserVersion, Self::Err> {
+        if matches!(
+            s.trim().to_lowercase().as_str(),
+            "node" | "stable" | "latest"
+        ) {
+            return Ok(Self::Latest);
+        }
+        if VersionRange::looks_like_range(s) {
+            return VersionRange::parse(s).map(Self::Range);
+        }
         match Version::parse(s) {
             Ok(v) => Ok(Self::Full(v)),
             Err(e) => {
@@ -93,6 +111,8 @@ impl PartialEq for UserVersion {
             (Self::OnlyMajor(a), Self::OnlyMajor(b)) if a == b => true,
             (Self::MajorMinor(a1, a2), Self::MajorMinor(b1, b2)) if (a1, a2) == (b1, b2) => true,
             (Self::Full(v1), Self::Full(v2)) if v1 == v2 => true,
+            (Self::Range(a), Self::Range(b)) if a == b => true,
+            (Self::Latest, Self::Latest) => true,
             (_, _) => false,
         }
     }
@@ -122,6 +142,56 @@ mod tests {
         assert_eq!(version, Some(UserVersion::OnlyMajor(10)));
     }
 
+    #[test]
+    fn test_parsing_range() {
+        let version = UserVersion::from_str(">=16.13 <17").ok();
+        assert_eq!(
+            version,
+            Some(UserVersion::Range(
+                VersionRange::parse(">=16.13 <17").unwrap()
+            ))
+        );
+        assert_eq!(version.unwrap().to_string(), ">=16.13 <17");
+    }
+
+    #[test]
+    fn test_parsing_lts_is_not_a_range() {
+        let version = UserVersion::from_str("lts/*").ok();
+        assert!(matches!(version, Some(UserVersion::Full(Version::Lts(_)))));
+    }
+
+    #[test]
+    fn test_range_to_version() {
+        let expected = Version::parse("16.20.0").unwrap();
+        let versions = vec![
+            Version::parse("14.21.3").unwrap(),
+            Version::parse("16.13.0").unwrap(),
+            expected.clone(),
+            Version::parse("17.0.0").unwrap(),
+        ];
+        let result = UserVersion::from_str(">=16.13 <17")
+            .unwrap()
+            .to_version(&versions, &FnmConfig::default());
+
+        assert_eq!(result, Some(&expected));
+    }
+
+    #[test]
+    fn test_latest_to_version() {
+        let expected = Version::parse("18.0.0").unwrap();
+        let versions = vec![
+            Version::parse("16.20.0").unwrap(),
+            expected.clone(),
+            Version::parse("17.9.1").unwrap(),
+        ];
+        for alias in ["node", "stable", "latest"] {
+            let user_version = UserVersion::from_str(alias).unwrap();
+            assert_eq!(user_version, UserVersion::Latest);
+            let result = user_version.to_version(&versions, &FnmConfig::default());
+            assert_eq!(result, Some(&expected));
+        }
+    }
+
     #[test]
     fn test_major_to_version() {
         let expected = Version::parse("6.1.0").unwrap();
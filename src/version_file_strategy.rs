@@ -1,7 +1,8 @@
 use std::str::FromStr;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub enum VersionFileStrategy {
+    #[default]
     Local,
     Recursive,
 }
@@ -19,12 +20,6 @@ impl VersionFileStrategy {
     }
 }
 
-impl Default for VersionFileStrategy {
-    fn default() -> Self {
-        VersionFileStrategy::Local
-    }
-}
-
 impl FromStr for VersionFileStrategy {
     type Err = String;
 
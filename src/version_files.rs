@@ -2,34 +2,36 @@ use crate::config::FnmConfig;
 use crate::default_version;
 use crate::user_version::UserVersion;
 use crate::version_file_strategy::VersionFileStrategy;
+use crate::version_source::{read_engines, read_tool_versions, read_volta_pin};
+use crate::version_source::{PACKAGE_JSON, TOOL_VERSIONS};
 use encoding_rs_io::DecodeReaderBytes;
 use log::info;
 use std::io::Read;
 use std::path::Path;
 use std::str::FromStr;
 
-const PATH_PARTS: [&str; 2] = [".nvmrc", ".node-version"];
-
 pub fn get_user_version_for_directory(
     path: impl AsRef<Path>,
     config: &FnmConfig,
 ) -> Option<UserVersion> {
     match config.version_file_strategy() {
-        VersionFileStrategy::Local => get_user_version_for_single_directory(path),
-        VersionFileStrategy::Recursive => {
-            get_user_version_for_directory_recursive(path).or_else(|| {
+        VersionFileStrategy::Local => get_user_version_for_single_directory(path, config),
+        VersionFileStrategy::Recursive => get_user_version_for_directory_recursive(path, config)
+            .or_else(|| {
                 info!("Did not find anything recursively. Falling back to default alias.");
                 default_version::find_default_version(config).map(UserVersion::Full)
-            })
-        }
+            }),
     }
 }
 
-fn get_user_version_for_directory_recursive(path: impl AsRef<Path>) -> Option<UserVersion> {
+fn get_user_version_for_directory_recursive(
+    path: impl AsRef<Path>,
+    config: &FnmConfig,
+) -> Option<UserVersion> {
     let mut current_path = Some(path.as_ref());
 
     while let Some(child_path) = current_path {
-        if let Some(version) = get_user_version_for_single_directory(child_path) {
+        if let Some(version) = get_user_version_for_single_directory(child_path, config) {
             return Some(version);
         }
 
@@ -39,25 +41,33 @@ fn get_user_version_for_directory_recursive(path: impl AsRef<Path>) -> Option<Us
     None
 }
 
-pub fn get_user_version_for_single_directory(path: impl AsRef<Path>) -> Option<UserVersion> {
+pub fn get_user_version_for_single_directory(
+    path: impl AsRef<Path>,
+    config: &FnmConfig,
+) -> Option<UserVersion> {
     let path = path.as_ref();
-
-    for path_part in &PATH_PARTS {
-        let new_path = path.join(path_part);
+    config.version_sources().iter().find_map(|source| {
         info!(
-            "Looking for version file in {}. exists? {}",
-            new_path.display(),
-            new_path.exists()
+            "Looking for a version in {} ({})",
+            path.display(),
+            source.as_str()
         );
-        if let Some(version) = get_user_version_for_file(&new_path) {
-            return Some(version);
-        }
-    }
-
-    None
+        source.find_in(path, config)
+    })
 }
 
+/// The version in the file at `path`, parsed according to its name
 pub fn get_user_version_for_file(path: impl AsRef<Path>) -> Option<UserVersion> {
+    let path = path.as_ref();
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(PACKAGE_JSON) => read_volta_pin(path).or_else(|| read_engines(path)),
+        Some(TOOL_VERSIONS) => read_tool_versions(path),
+        _ => read_version_file(path),
+    }
+}
+
+/// The version in a file that contains only the version, like `.nvmrc`
+pub fn read_version_file(path: &Path) -> Option<UserVersion> {
     let file = std::fs::File::open(path).ok()?;
     let version = {
         let mut reader = DecodeReaderBytes::new(file);
@@ -76,3 +86,66 @@ pub fn get_user_version_for_file(path: impl AsRef<Path>) -> Option<UserVersion>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_version_sources_priority() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(directory.path().join(".node-version"), "14").unwrap();
+        std::fs::write(directory.path().join(TOOL_VERSIONS), "nodejs 16.20.0").unwrap();
+        std::fs::write(
+            directory.path().join(PACKAGE_JSON),
+            r#"{ "volta": { "node": "18.12.1" } }"#,
+        )
+        .unwrap();
+        let version_with_sources = |sources: &str| {
+            let config = FnmConfig::default().with_version_sources(sources);
+            get_user_version_for_single_directory(directory.path(), &config)
+                .map(|version| version.to_string())
+        };
+
+        assert_eq!(
+            version_with_sources("version-files"),
+            Some("v14.x.x".into())
+        );
+        assert_eq!(
+            version_with_sources("volta,tool-versions,version-files"),
+            Some("v18.12.1".into())
+        );
+        assert_eq!(
+            version_with_sources("tool-versions,volta"),
+            Some("v16.20.0".into())
+        );
+        assert_eq!(version_with_sources("engines"), None);
+    }
+
+    #[test]
+    fn test_version_files_priority() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(directory.path().join(".nvmrc"), "14").unwrap();
+        std::fs::write(directory.path().join(".node-version"), "16").unwrap();
+        let version_with_files = |files: &[&str]| {
+            let config = FnmConfig::default().with_version_files(files);
+            get_user_version_for_single_directory(directory.path(), &config)
+                .map(|version| version.to_string())
+        };
+
+        assert_eq!(
+            version_with_files(&[".nvmrc", ".node-version"]),
+            Some("v14.x.x".into())
+        );
+        assert_eq!(
+            version_with_files(&[".node-version", ".nvmrc"]),
+            Some("v16.x.x".into())
+        );
+        assert_eq!(
+            version_with_files(&[".node-version"]),
+            Some("v16.x.x".into())
+        );
+        assert_eq!(version_with_files(&[".fnmrc"]), None);
+    }
+}
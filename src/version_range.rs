@@ -0,0 +1,171 @@
+//! npm-style version ranges, like `>=16.13 <17`, `^18.2` or `14.x || 16.x`,
+//! as written in `.nvmrc` files and in `engines.node` of `package.json`.
+//!
+//! The `semver` crate understands most comparators, but npm separates them with spaces
+//! instead of commas, has `||` unions and hyphen ranges, and treats a version without
+//! an operator as an exact version instead of a caret requirement.
+
+use semver::VersionReq;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionRange {
+    /// The range as it was written, which is how it's shown in messages
+    raw: String,
+    /// A version is in the range when it matches any of these
+    alternatives: Vec<VersionReq>,
+}
+
+impl VersionRange {
+    pub fn parse(range: &str) -> Result<Self, semver::Error> {
+        let alternatives = range
+            .split("||")
+            .map(parse_comparator_set)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            raw: range.trim().to_string(),
+            alternatives,
+        })
+    }
+
+    pub fn matches(&self, version: &semver::Version) -> bool {
+        self.alternatives.iter().any(|req| req.matches(version))
+    }
+
+    /// Whether `s` is written as a range, rather than as a version or an alias.
+    /// `lts/*` is the latest LTS release, not a wildcard.
+    pub fn looks_like_range(s: &str) -> bool {
+        let s = s.trim();
+        if s.to_lowercase().starts_with("lts") {
+            return false;
+        }
+        let has_range_syntax = s.contains(|c: char| "<>=~^|*".contains(c) || c.is_whitespace());
+        let is_wildcard_version = s
+            .trim_start_matches('v')
+            .starts_with(|c: char| c.is_ascii_digit())
+            && s.split('.').any(|part| part.eq_ignore_ascii_case("x"));
+        has_range_syntax || is_wildcard_version
+    }
+}
+
+impl std::fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+/// Parses space-separated comparators that must all match, like `>=16.13 <17`
+fn parse_comparator_set(set: &str) -> Result<VersionReq, semver::Error> {
+    let tokens: Vec<&str> = set.split_whitespace().collect();
+
+    // A hyphen range, like `16 - 18`, includes both ends
+    if let [from, "-", to] = tokens.as_slice() {
+        return VersionReq::parse(&format!(
+            ">={}, <={}",
+            without_wildcards(from),
+            without_wildcards(to)
+        ));
+    }
+
+    let mut comparators = vec![];
+    let mut pending_operator = String::new();
+    for token in tokens {
+        let version_start = token
+            .find(|c: char| !"<>=~^".contains(c))
+            .unwrap_or(token.len());
+        let (operator, version) = token.split_at(version_start);
+        pending_operator.push_str(operator);
+        if version.is_empty() {
+            // An operator separated from its version, like `>= 16`
+            continue;
+        }
+
+        let version = without_wildcards(version);
+        if !version.is_empty() {
+            let operator = if pending_operator.is_empty() {
+                "="
+            } else {
+                pending_operator.as_str()
+            };
+            comparators.push(format!("{}{}", operator, version));
+        }
+        pending_operator.clear();
+    }
+
+    if comparators.is_empty() {
+        // `*`, `x` or an empty range match any version
+        return Ok(VersionReq::STAR);
+    }
+    VersionReq::parse(&comparators.join(", "))
+}
+
+/// The version without its leading `v` and its wildcard parts, like `16` for `v16.x.x`.
+/// A partial version matches any version it is a prefix of.
+fn without_wildcards(version: &str) -> String {
+    version
+        .trim_start_matches('v')
+        .split('.')
+        .take_while(|part| !matches!(*part, "x" | "X" | "*"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn matching(range: &str, versions: &[&str]) -> Vec<String> {
+        let range = VersionRange::parse(range).unwrap();
+        versions
+            .iter()
+            .filter(|version| range.matches(&semver::Version::parse(version).unwrap()))
+            .map(|version| (*version).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_matches() {
+        let versions = [
+            "14.21.3", "16.0.0", "16.13.0", "16.20.2", "17.0.0", "18.2.0", "18.20.4", "20.0.0",
+        ];
+        let cases: &[(&str, &[&str])] = &[
+            (">=16.13 <17", &["16.13.0", "16.20.2"]),
+            (">= 16.13 < 17", &["16.13.0", "16.20.2"]),
+            ("^18.2", &["18.2.0", "18.20.4"]),
+            ("~16.13", &["16.13.0"]),
+            ("16", &["16.0.0", "16.13.0", "16.20.2"]),
+            ("18.2.0", &["18.2.0"]),
+            ("v16.x", &["16.0.0", "16.13.0", "16.20.2"]),
+            ("16.13.x", &["16.13.0"]),
+            ("14.x || >=18.20", &["14.21.3", "18.20.4", "20.0.0"]),
+            ("16 - 17", &["16.0.0", "16.13.0", "16.20.2", "17.0.0"]),
+            ("*", &versions),
+        ];
+        for (range, expected) in cases {
+            assert_eq!(&matching(range, &versions), expected, "{}", range);
+        }
+    }
+
+    #[test]
+    fn test_looks_like_range() {
+        for range in [">=16", "^18.2", "~16", "16.x", "v16.X.x", "14 || 16", "*"] {
+            assert!(VersionRange::looks_like_range(range), "{}", range);
+        }
+        for not_range in [
+            "16",
+            "16.13",
+            "v18.12.1",
+            "lts/*",
+            "lts/hydrogen",
+            "default",
+        ] {
+            assert!(!VersionRange::looks_like_range(not_range), "{}", not_range);
+        }
+    }
+
+    #[test]
+    fn test_display_original_range() {
+        let range = VersionRange::parse(" >=16.13 <17 ").unwrap();
+        assert_eq!(range.to_string(), ">=16.13 <17");
+    }
+}
@@ -0,0 +1,234 @@
+//! The places a project can declare its Node version in, like `.nvmrc` or `package.json`.
+//! They are looked up in the order given in `--version-sources`.
+
+use crate::config::FnmConfig;
+use crate::user_version::UserVersion;
+use crate::version_files::read_version_file;
+use crate::version_range::VersionRange;
+use log::info;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const PACKAGE_JSON: &str = "package.json";
+pub const TOOL_VERSIONS: &str = ".tool-versions";
+
+/// How many `volta.extends` are followed to find the pinned version
+const MAX_VOLTA_EXTENDS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+    /// The files in `--version-files`, like `.nvmrc`, which contain only the version
+    VersionFiles,
+    /// asdf's `.tool-versions`, with a line like `nodejs 18.12.1`
+    ToolVersions,
+    /// The `volta.node` pin of `package.json`
+    Volta,
+    /// The `engines.node` range of `package.json`
+    Engines,
+}
+
+impl VersionSource {
+    pub fn possible_values() -> &'static [&'static str] {
+        &["version-files", "tool-versions", "volta", "engines"]
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::VersionFiles => "version-files",
+            Self::ToolVersions => "tool-versions",
+            Self::Volta => "volta",
+            Self::Engines => "engines",
+        }
+    }
+
+    /// The version declared in the directory `dir`, if any
+    pub fn find_in(self, dir: &Path, config: &FnmConfig) -> Option<UserVersion> {
+        match self {
+            Self::VersionFiles => config.version_files().iter().find_map(|file_name| {
+                let path = dir.join(file_name);
+                info!(
+                    "Looking for version file in {}. exists? {}",
+                    path.display(),
+                    path.exists()
+                );
+                read_version_file(&path)
+            }),
+            Self::ToolVersions => read_tool_versions(&dir.join(TOOL_VERSIONS)),
+            Self::Volta => read_volta_pin(&dir.join(PACKAGE_JSON)),
+            Self::Engines => read_engines(&dir.join(PACKAGE_JSON)),
+        }
+    }
+
+    /// The files in a directory this source reads the version from
+    pub fn file_names(self, config: &FnmConfig) -> Vec<&str> {
+        match self {
+            Self::VersionFiles => config.version_files().iter().map(String::as_str).collect(),
+            Self::ToolVersions => vec![TOOL_VERSIONS],
+            Self::Volta | Self::Engines => vec![PACKAGE_JSON],
+        }
+    }
+}
+
+impl FromStr for VersionSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "version-files" => Ok(Self::VersionFiles),
+            "tool-versions" => Ok(Self::ToolVersions),
+            "volta" => Ok(Self::Volta),
+            "engines" => Ok(Self::Engines),
+            _ => Err(format!(
+                "Invalid version source: {}. Expected one of: {}",
+                s,
+                Self::possible_values().join(", ")
+            )),
+        }
+    }
+}
+
+/// The version in a `.tool-versions` file. asdf tries the versions of a line in order,
+/// so the first one is the preferred version.
+pub fn read_tool_versions(path: &Path) -> Option<UserVersion> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let version = contents.lines().find_map(|line| {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut parts = line.split_whitespace();
+        if !matches!(parts.next(), Some("nodejs" | "node")) {
+            return None;
+        }
+        // Versions like `ref:v18.12.1` or `path:/opt/node` are built or managed by asdf
+        parts.find(|version| !version.contains(':'))
+    })?;
+    info!("Found {:?} in {}", version, path.display());
+    UserVersion::from_str(version).ok()
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+    engines: Option<Engines>,
+    volta: Option<Volta>,
+}
+
+#[derive(Deserialize)]
+struct Engines {
+    node: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Volta {
+    node: Option<String>,
+    /// Another `package.json` to take the pins from, relative to this one
+    extends: Option<PathBuf>,
+}
+
+fn read_package_json(path: &Path) -> Option<PackageJson> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(package_json) => Some(package_json),
+        Err(err) => {
+            info!("Can't parse {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+/// The `volta.node` pin of a `package.json` file, or of the file it extends
+pub fn read_volta_pin(path: &Path) -> Option<UserVersion> {
+    let mut path = path.to_path_buf();
+    for _ in 0..MAX_VOLTA_EXTENDS {
+        let volta = read_package_json(&path)?.volta?;
+        if let Some(version) = volta.node {
+            info!("Found volta.node {:?} in {}", version, path.display());
+            return UserVersion::from_str(&version).ok();
+        }
+        path = path.parent()?.join(volta.extends?);
+    }
+    None
+}
+
+/// The `engines.node` range of a `package.json` file
+pub fn read_engines(path: &Path) -> Option<UserVersion> {
+    let range = read_package_json(path)?.engines?.node?;
+    info!("Found engines.node {:?} in {}", range, path.display());
+    match VersionRange::parse(&range) {
+        Ok(range) => Some(UserVersion::Range(range)),
+        Err(err) => {
+            info!("Can't parse engines.node {:?}: {}", range, err);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_tool_versions() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(
+            directory.path().join(TOOL_VERSIONS),
+            "# runtimes\nruby 3.2.0\nnodejs ref:v19.0.0 18.12.1 16.20.0 # LTS\n",
+        )
+        .unwrap();
+        assert_eq!(
+            VersionSource::ToolVersions.find_in(directory.path(), &FnmConfig::default()),
+            Some(UserVersion::Full(Version::parse("18.12.1").unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_volta_extends() {
+        let directory = tempfile::tempdir().unwrap();
+        let package = directory.path().join("packages").join("app");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(
+            directory.path().join(PACKAGE_JSON),
+            r#"{ "volta": { "node": "18.12.1", "npm": "9.2.0" } }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            package.join(PACKAGE_JSON),
+            r#"{ "name": "app", "volta": { "extends": "../../package.json" } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            VersionSource::Volta.find_in(&package, &FnmConfig::default()),
+            Some(UserVersion::Full(Version::parse("18.12.1").unwrap()))
+        );
+        assert_eq!(
+            VersionSource::Engines.find_in(&package, &FnmConfig::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_engines() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(
+            directory.path().join(PACKAGE_JSON),
+            r#"{ "name": "app", "engines": { "node": ">=16.13 <17" } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            VersionSource::Engines.find_in(directory.path(), &FnmConfig::default()),
+            Some(UserVersion::Range(
+                VersionRange::parse(">=16.13 <17").unwrap()
+            ))
+        );
+        assert_eq!(
+            VersionSource::Volta.find_in(directory.path(), &FnmConfig::default()),
+            None
+        );
+
+        std::fs::write(directory.path().join(PACKAGE_JSON), r#"{ "name": "app" }"#).unwrap();
+        assert_eq!(
+            VersionSource::Engines.find_in(directory.path(), &FnmConfig::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,8 @@
+test_shell!(Bash, Zsh; {
+    EvalFnmEnv::default()
+        .then(ExpectCommandOutput::new(
+            Call::new("fnm", vec!["install", "v8.11.3", "2>&1", ">/dev/null"]),
+            "",
+            "the download progress when stderr is not a terminal",
+        ))
+});
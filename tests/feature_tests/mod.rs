@@ -1,5 +1,6 @@
 mod aliases;
 mod current;
+mod download_progress;
 mod uninstall;
 
 use crate::shellcode::*;
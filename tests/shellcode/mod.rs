@@ -29,7 +29,6 @@ pub use get_stderr::*;
 #[allow(unused)]
 pub use ignore_errors::*;
 #[allow(unused)]
-#[allow(unused)]
 pub use line_separated_expressions::*;
 #[allow(unused)]
 pub use nothing::*;
@@ -3,9 +3,10 @@ use std::fmt::Debug;
 
 pub(crate) trait Shell: Debug {
     fn currently_supported(&self) -> bool;
+    #[allow(dead_code)]
     fn name(&self) -> &'static str;
     fn binary_name(&self) -> &'static str;
-    fn shell_escape(str: &str) -> Cow<str>;
+    fn shell_escape(str: &str) -> Cow<'_, str>;
     fn launch_args(&self) -> &'static [&'static str] {
         &[]
     }
@@ -23,7 +24,7 @@ impl Shell for Fish {
     fn binary_name(&self) -> &'static str {
         "fish"
     }
-    fn shell_escape(str: &str) -> Cow<str> {
+    fn shell_escape(str: &str) -> Cow<'_, str> {
         shell_escape::unix::escape(Cow::from(str))
     }
 }
@@ -40,7 +41,7 @@ impl Shell for Bash {
     fn binary_name(&self) -> &'static str {
         "bash"
     }
-    fn shell_escape(str: &str) -> Cow<str> {
+    fn shell_escape(str: &str) -> Cow<'_, str> {
         shell_escape::unix::escape(Cow::from(str))
     }
 }
@@ -57,7 +58,7 @@ impl Shell for Zsh {
     fn binary_name(&self) -> &'static str {
         "zsh"
     }
-    fn shell_escape(str: &str) -> Cow<str> {
+    fn shell_escape(str: &str) -> Cow<'_, str> {
         shell_escape::unix::escape(Cow::from(str))
     }
 }
@@ -74,7 +75,7 @@ impl Shell for WinCmd {
     fn binary_name(&self) -> &'static str {
         "cmd"
     }
-    fn shell_escape(str: &str) -> Cow<str> {
+    fn shell_escape(str: &str) -> Cow<'_, str> {
         Cow::from(
             str.replace('\r', "")
                 .replace('\n', "^\n\n")
@@ -102,7 +103,7 @@ impl Shell for PowerShell {
             "pwsh"
         }
     }
-    fn shell_escape(str: &str) -> Cow<str> {
+    fn shell_escape(str: &str) -> Cow<'_, str> {
         let new_str = format!("'{}'", str.replace('\'', "''"));
         Cow::from(new_str)
     }